@@ -0,0 +1,139 @@
+//! Derive macros for [`libui`](https://docs.rs/libui). Enable the `derive` feature of `libui`
+//! to use them through `libui::controls`.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{Data, DeriveInput, Error, Fields, LitStr, Variant};
+
+/// Implements `libui::controls::Choice` for an enum without fields, so it can be picked from a
+/// `Combobox` or `RadioButtons`.
+///
+/// The variants are offered in the order they are declared. Each is labelled with its name,
+/// unless a label is given with `#[choice(label = "...")]`.
+#[proc_macro_derive(Choice, attributes(choice))]
+pub fn derive_choice(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_choice(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_choice(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let data = match input.data {
+        Data::Enum(ref data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "`Choice` can only be derived for enums",
+            ))
+        }
+    };
+    if data.variants.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`Choice` can not be derived for enums without variants",
+        ));
+    }
+
+    let name = &input.ident;
+    let mut variants = Vec::new();
+    let mut labels = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "`Choice` can only be derived for enums whose variants have no fields",
+            ));
+        }
+        variants.push(&variant.ident);
+        labels.push(label(variant)?);
+    }
+    let indices: Vec<usize> = (0..variants.len()).collect();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::libui::controls::Choice for #name #ty_generics #where_clause {
+            fn from_index(index: usize) -> ::std::option::Option<Self> {
+                match index {
+                    #(#indices => ::std::option::Option::Some(#name::#variants),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn index(&self) -> usize {
+                match *self {
+                    #(#name::#variants => #indices,)*
+                }
+            }
+
+            fn label(&self) -> &'static str {
+                match *self {
+                    #(#name::#variants => #labels,)*
+                }
+            }
+        }
+    })
+}
+
+/// Returns the label given with `#[choice(label = "...")]`, or the name of the variant.
+fn label(variant: &Variant) -> syn::Result<String> {
+    let mut label = None;
+    for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("choice")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("label") {
+                label = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unknown `choice` attribute, expected `label`"))
+            }
+        })?;
+    }
+    Ok(label.unwrap_or_else(|| variant.ident.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(input: TokenStream2) -> Result<String, String> {
+        let input = syn::parse2(input).unwrap();
+        expand_choice(&input)
+            .map(|tokens| tokens.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn labels_default_to_the_variant_name() {
+        let expanded = expand(quote! {
+            enum Quality {
+                Low,
+                #[choice(label = "Very high")]
+                VeryHigh,
+            }
+        })
+        .unwrap();
+        assert!(expanded.contains("\"Low\""));
+        assert!(expanded.contains("\"Very high\""));
+        assert!(!expanded.contains("\"VeryHigh\""));
+    }
+
+    #[test]
+    fn only_enums_without_fields_are_accepted() {
+        assert!(expand(quote! { struct Quality; }).is_err());
+        assert!(expand(quote! { enum Quality {} }).is_err());
+        assert!(expand(quote! { enum Quality { Low, Custom(u8) } }).is_err());
+        let unknown = expand(quote! {
+            enum Quality {
+                #[choice(name = "Low")]
+                Low,
+            }
+        });
+        assert!(unknown.unwrap_err().contains("expected `label`"));
+    }
+}
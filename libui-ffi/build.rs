@@ -10,6 +10,11 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() {
+    // The mock backend is written in Rust, there is nothing to generate or build
+    if cfg!(feature = "mock") {
+        return;
+    }
+
     // Determine build platform
     let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
     let target_triple = env::var("TARGET").unwrap();
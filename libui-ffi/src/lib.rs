@@ -1,8 +1,34 @@
 //! Autogenerated bindings to [`libui-ng`](https://github.com/libui-ng/libui-ng) for use in the high-level rust version of [`libui`](https://github.com/libui-rs/libui). It is not meant to be used directly.
+//!
+//! With the `mock` feature, `libui-ng` is replaced by an in-memory implementation in Rust, see
+//! the [`mock`](mock/index.html) module.
 
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 #![allow(deref_nullptr)] // bindgen needs to use UB: https://github.com/rust-lang/rust-bindgen/issues/1651
 
-include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
\ No newline at end of file
+extern crate libc;
+
+#[cfg(not(feature = "mock"))]
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
+#[cfg(feature = "mock")]
+pub use mock::controls::*;
+#[cfg(feature = "mock")]
+pub use mock::dialogs::*;
+#[cfg(feature = "mock")]
+pub use mock::draw::*;
+#[cfg(feature = "mock")]
+pub use mock::main::*;
+#[cfg(feature = "mock")]
+pub use mock::menus::*;
+#[cfg(feature = "mock")]
+pub use mock::table::*;
+#[cfg(feature = "mock")]
+pub use mock::types::*;
+#[cfg(feature = "mock")]
+pub use mock::windows::*;
@@ -0,0 +1,1018 @@
+//! Controls, kept as nodes in memory.
+
+use libc;
+use mock::types::*;
+use mock::user_bug;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+/// A callback set on a control, along with its data.
+#[derive(Clone, Copy)]
+pub(crate) struct Callback {
+    pub f: *const (),
+    pub data: Data,
+}
+
+/// A child of a container, along with the properties the container keeps for it.
+pub(crate) struct Child {
+    pub control: *mut uiControl,
+    pub label: CString,
+    pub stretchy: bool,
+    pub margined: bool,
+}
+
+/// The state of a control. Which fields are used depends on the kind of control.
+#[repr(C)]
+pub(crate) struct Node {
+    // Must come first, so a pointer to the node is a pointer to the `uiControl`.
+    control: uiControl,
+    pub kind: &'static str,
+    pub parent: *mut uiControl,
+    pub children: Vec<Child>,
+    pub visible: bool,
+    pub enabled: bool,
+    pub text: CString,
+    pub items: Vec<CString>,
+    pub value: c_int,
+    pub range: (c_int, c_int),
+    pub flags: HashSet<&'static str>,
+    pub position: (c_int, c_int),
+    pub size: (c_int, c_int),
+    pub color: (f64, f64, f64, f64),
+    pub time: tm,
+    pub callbacks: HashMap<&'static str, Callback>,
+    pub table: Option<Box<::mock::table::TableState>>,
+    pub area_handler: *mut uiAreaHandler,
+}
+
+thread_local! {
+    static NODES: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+    static WINDOWS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Creates a new control of the given kind, such as `"uiButton"`.
+pub(crate) fn create<T>(kind: &'static str, text: *const c_char) -> *mut T {
+    let text = if text.is_null() {
+        CString::default()
+    } else {
+        unsafe { CStr::from_ptr(text) }.to_owned()
+    };
+    let node = Box::new(Node {
+        control: uiControl {
+            Signature: 0x7569436F,
+            OSSignature: 0x4D6F636B,
+            TypeSignature: 0,
+            Destroy: Some(uiControlDestroy),
+            Handle: Some(uiControlHandle),
+            Parent: Some(uiControlParent),
+            SetParent: Some(uiControlSetParent),
+            Toplevel: Some(uiControlToplevel),
+            Visible: Some(uiControlVisible),
+            Show: Some(uiControlShow),
+            Hide: Some(uiControlHide),
+            Enabled: Some(uiControlEnabled),
+            Enable: Some(uiControlEnable),
+            Disable: Some(uiControlDisable),
+        },
+        kind,
+        parent: ptr::null_mut(),
+        children: Vec::new(),
+        // Windows are the only controls which are hidden at first.
+        visible: kind != "uiWindow",
+        enabled: true,
+        text,
+        items: Vec::new(),
+        value: 0,
+        range: (0, 0),
+        flags: HashSet::new(),
+        position: (0, 0),
+        size: (0, 0),
+        color: (0.0, 0.0, 0.0, 1.0),
+        time: epoch(),
+        callbacks: HashMap::new(),
+        table: None,
+        area_handler: ptr::null_mut(),
+    });
+    let control = Box::into_raw(node);
+    NODES.with(|nodes| nodes.borrow_mut().insert(control as usize));
+    if kind == "uiWindow" {
+        WINDOWS.with(|windows| windows.borrow_mut().push(control as usize));
+    }
+    control as *mut T
+}
+
+/// Midnight of the first of January, 1970, in local time.
+fn epoch() -> tm {
+    let mut time: tm = unsafe { mem::zeroed() };
+    time.tm_mday = 1;
+    time.tm_year = 70;
+    time.tm_wday = 4;
+    time.tm_isdst = -1;
+    time
+}
+
+pub(crate) fn is_alive<T>(control: *mut T) -> bool {
+    NODES.with(|nodes| nodes.borrow().contains(&(control as usize)))
+}
+
+/// Runs `f` on the state of the control.
+///
+/// The node must not be accessed again from within `f`, callbacks are called after it returns.
+pub(crate) fn with<T, R, F: FnOnce(&mut Node) -> R>(control: *mut T, f: F) -> R {
+    if !is_alive(control) {
+        user_bug(&format!(
+            "{:p} is not a control, or was destroyed already",
+            control
+        ));
+    }
+    f(unsafe { &mut *(control as *mut Node) })
+}
+
+/// Returns a copy of the text of the control, to be freed with `uiFreeText`.
+pub(crate) fn text<T>(control: *mut T) -> *mut c_char {
+    with(control, |node| node.text.clone().into_raw())
+}
+
+pub(crate) fn set_text<T>(control: *mut T, text: *const c_char) {
+    let text = unsafe { CStr::from_ptr(text) }.to_owned();
+    with(control, |node| node.text = text);
+}
+
+pub(crate) fn flag<T>(control: *mut T, flag: &'static str) -> c_int {
+    with(control, |node| node.flags.contains(flag) as c_int)
+}
+
+pub(crate) fn set_flag<T>(control: *mut T, flag: &'static str, value: c_int) {
+    with(control, |node| {
+        if value != 0 {
+            node.flags.insert(flag);
+        } else {
+            node.flags.remove(flag);
+        }
+    });
+}
+
+/// Sets or clears the callback for `event`. `f` is any `extern "C"` function pointer.
+pub(crate) fn set_callback<T>(
+    control: *mut T,
+    event: &'static str,
+    f: Option<*const ()>,
+    data: Data,
+) {
+    with(control, |node| match f {
+        Some(f) => node.callbacks.insert(event, Callback { f, data }),
+        None => node.callbacks.remove(event),
+    });
+}
+
+pub(crate) fn callback<T>(control: *mut T, event: &'static str) -> Option<Callback> {
+    with(control, |node| node.callbacks.get(event).cloned())
+}
+
+/// Makes `child` a child of `parent`, as containers do.
+pub(crate) fn attach(parent: *mut uiControl, child: *mut uiControl) {
+    with(child, |node| {
+        if node.kind == "uiWindow" {
+            user_bug("cannot make a uiWindow the child of another control");
+        }
+        if !node.parent.is_null() {
+            user_bug("cannot give a control a parent while it already has one");
+        }
+        node.parent = parent;
+    });
+}
+
+/// Removes the child at `index` from the container, without destroying it.
+pub(crate) fn detach<T>(parent: *mut T, index: c_int) {
+    let child = with(parent, |node| {
+        if index < 0 || index as usize >= node.children.len() {
+            user_bug(&format!("index {} out of range", index));
+        }
+        node.children.remove(index as usize)
+    });
+    with(child.control, |node| node.parent = ptr::null_mut());
+}
+
+/// Appends a child to the container.
+pub(crate) fn append_child<T>(
+    parent: *mut T,
+    child: *mut uiControl,
+    label: *const c_char,
+    stretchy: c_int,
+) {
+    insert_child(parent, None, child, label, stretchy);
+}
+
+/// Inserts a child into the container at `index`, or appends it if `None`.
+pub(crate) fn insert_child<T>(
+    parent: *mut T,
+    index: Option<usize>,
+    child: *mut uiControl,
+    label: *const c_char,
+    stretchy: c_int,
+) {
+    attach(parent as *mut uiControl, child);
+    let label = if label.is_null() {
+        CString::default()
+    } else {
+        unsafe { CStr::from_ptr(label) }.to_owned()
+    };
+    let child = Child {
+        control: child,
+        label,
+        stretchy: stretchy != 0,
+        margined: false,
+    };
+    with(parent, |node| match index {
+        Some(index) if index <= node.children.len() => node.children.insert(index, child),
+        Some(index) => user_bug(&format!("index {} out of range", index)),
+        None => node.children.push(child),
+    });
+}
+
+/// Replaces the only child of a window or group, orphaning the previous one.
+pub(crate) fn set_only_child<T>(parent: *mut T, child: *mut uiControl) {
+    if with(parent, |node| !node.children.is_empty()) {
+        detach(parent, 0);
+    }
+    if !child.is_null() {
+        append_child(parent, child, ptr::null(), 0);
+    }
+}
+
+pub(crate) fn num_children<T>(parent: *mut T) -> c_int {
+    with(parent, |node| node.children.len() as c_int)
+}
+
+/// Returns the open windows, oldest first.
+pub(crate) fn windows() -> Vec<*mut uiWindow> {
+    WINDOWS.with(|windows| {
+        windows
+            .borrow()
+            .iter()
+            .map(|&w| w as *mut uiWindow)
+            .collect()
+    })
+}
+
+/// Destroys all controls without a parent. Used when uninitializing.
+pub(crate) fn destroy_all() {
+    let roots: Vec<usize> = NODES.with(|nodes| {
+        nodes
+            .borrow()
+            .iter()
+            .cloned()
+            .filter(|&control| unsafe { (*(control as *mut Node)).parent.is_null() })
+            .collect()
+    });
+    for control in roots {
+        unsafe { uiControlDestroy(control as *mut uiControl) };
+    }
+}
+
+pub unsafe extern "C" fn uiControlDestroy(c: *mut uiControl) {
+    let children = with(c, |node| {
+        if !node.parent.is_null() {
+            user_bug("cannot destroy a control while it still has a parent");
+        }
+        mem::take(&mut node.children)
+    });
+    for child in children {
+        with(child.control, |node| node.parent = ptr::null_mut());
+        uiControlDestroy(child.control);
+    }
+    NODES.with(|nodes| nodes.borrow_mut().remove(&(c as usize)));
+    WINDOWS.with(|windows| windows.borrow_mut().retain(|&w| w != c as usize));
+    drop(Box::from_raw(c as *mut Node));
+}
+
+pub unsafe extern "C" fn uiControlHandle(c: *mut uiControl) -> usize {
+    with(c, |_| c as usize)
+}
+
+pub unsafe extern "C" fn uiControlParent(c: *mut uiControl) -> *mut uiControl {
+    with(c, |node| node.parent)
+}
+
+pub unsafe extern "C" fn uiControlSetParent(c: *mut uiControl, parent: *mut uiControl) {
+    if parent.is_null() {
+        with(c, |node| node.parent = ptr::null_mut());
+    } else {
+        attach(parent, c);
+    }
+}
+
+pub unsafe extern "C" fn uiControlToplevel(c: *mut uiControl) -> c_int {
+    with(c, |node| (node.kind == "uiWindow") as c_int)
+}
+
+pub unsafe extern "C" fn uiControlVisible(c: *mut uiControl) -> c_int {
+    with(c, |node| node.visible as c_int)
+}
+
+pub unsafe extern "C" fn uiControlShow(c: *mut uiControl) {
+    with(c, |node| node.visible = true);
+    if uiControlToplevel(c) != 0 {
+        ::mock::windows::focus(c as *mut uiWindow);
+    }
+}
+
+pub unsafe extern "C" fn uiControlHide(c: *mut uiControl) {
+    with(c, |node| node.visible = false);
+}
+
+pub unsafe extern "C" fn uiControlEnabled(c: *mut uiControl) -> c_int {
+    with(c, |node| node.enabled as c_int)
+}
+
+pub unsafe extern "C" fn uiControlEnable(c: *mut uiControl) {
+    with(c, |node| node.enabled = true);
+}
+
+pub unsafe extern "C" fn uiControlDisable(c: *mut uiControl) {
+    with(c, |node| node.enabled = false);
+}
+
+pub unsafe extern "C" fn uiButtonText(b: *mut uiButton) -> *mut c_char {
+    text(b)
+}
+
+pub unsafe extern "C" fn uiButtonSetText(b: *mut uiButton, text: *const c_char) {
+    set_text(b, text)
+}
+
+pub unsafe extern "C" fn uiButtonOnClicked(
+    b: *mut uiButton,
+    f: Option<unsafe extern "C" fn(b: *mut uiButton, data: Data)>,
+    data: Data,
+) {
+    set_callback(b, "clicked", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewButton(text: *const c_char) -> *mut uiButton {
+    create("uiButton", text)
+}
+
+pub unsafe extern "C" fn uiBoxAppend(b: *mut uiBox, child: *mut uiControl, stretchy: c_int) {
+    append_child(b, child, ptr::null(), stretchy)
+}
+
+pub unsafe extern "C" fn uiBoxNumChildren(b: *mut uiBox) -> c_int {
+    num_children(b)
+}
+
+pub unsafe extern "C" fn uiBoxDelete(b: *mut uiBox, index: c_int) {
+    detach(b, index)
+}
+
+pub unsafe extern "C" fn uiBoxPadded(b: *mut uiBox) -> c_int {
+    flag(b, "padded")
+}
+
+pub unsafe extern "C" fn uiBoxSetPadded(b: *mut uiBox, padded: c_int) {
+    set_flag(b, "padded", padded)
+}
+
+pub unsafe extern "C" fn uiNewHorizontalBox() -> *mut uiBox {
+    create("uiBox", ptr::null())
+}
+
+pub unsafe extern "C" fn uiNewVerticalBox() -> *mut uiBox {
+    create("uiBox", ptr::null())
+}
+
+pub unsafe extern "C" fn uiCheckboxText(c: *mut uiCheckbox) -> *mut c_char {
+    text(c)
+}
+
+pub unsafe extern "C" fn uiCheckboxSetText(c: *mut uiCheckbox, text: *const c_char) {
+    set_text(c, text)
+}
+
+pub unsafe extern "C" fn uiCheckboxOnToggled(
+    c: *mut uiCheckbox,
+    f: Option<unsafe extern "C" fn(c: *mut uiCheckbox, data: Data)>,
+    data: Data,
+) {
+    set_callback(c, "toggled", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiCheckboxChecked(c: *mut uiCheckbox) -> c_int {
+    with(c, |node| node.value)
+}
+
+pub unsafe extern "C" fn uiCheckboxSetChecked(c: *mut uiCheckbox, checked: c_int) {
+    with(c, |node| node.value = (checked != 0) as c_int)
+}
+
+pub unsafe extern "C" fn uiNewCheckbox(text: *const c_char) -> *mut uiCheckbox {
+    create("uiCheckbox", text)
+}
+
+pub unsafe extern "C" fn uiEntryText(e: *mut uiEntry) -> *mut c_char {
+    text(e)
+}
+
+pub unsafe extern "C" fn uiEntrySetText(e: *mut uiEntry, text: *const c_char) {
+    set_text(e, text)
+}
+
+pub unsafe extern "C" fn uiEntryOnChanged(
+    e: *mut uiEntry,
+    f: Option<unsafe extern "C" fn(e: *mut uiEntry, data: Data)>,
+    data: Data,
+) {
+    set_callback(e, "changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiEntryReadOnly(e: *mut uiEntry) -> c_int {
+    flag(e, "read_only")
+}
+
+pub unsafe extern "C" fn uiEntrySetReadOnly(e: *mut uiEntry, readonly: c_int) {
+    set_flag(e, "read_only", readonly)
+}
+
+pub unsafe extern "C" fn uiNewEntry() -> *mut uiEntry {
+    create("uiEntry", ptr::null())
+}
+
+pub unsafe extern "C" fn uiNewPasswordEntry() -> *mut uiEntry {
+    create("uiEntry", ptr::null())
+}
+
+pub unsafe extern "C" fn uiNewSearchEntry() -> *mut uiEntry {
+    create("uiEntry", ptr::null())
+}
+
+pub unsafe extern "C" fn uiLabelText(l: *mut uiLabel) -> *mut c_char {
+    text(l)
+}
+
+pub unsafe extern "C" fn uiLabelSetText(l: *mut uiLabel, text: *const c_char) {
+    set_text(l, text)
+}
+
+pub unsafe extern "C" fn uiNewLabel(text: *const c_char) -> *mut uiLabel {
+    create("uiLabel", text)
+}
+
+pub unsafe extern "C" fn uiTabAppend(t: *mut uiTab, name: *const c_char, c: *mut uiControl) {
+    let index = num_children(t);
+    uiTabInsertAt(t, name, index, c)
+}
+
+pub unsafe extern "C" fn uiTabInsertAt(
+    t: *mut uiTab,
+    name: *const c_char,
+    index: c_int,
+    c: *mut uiControl,
+) {
+    insert_child(t, Some(index.max(0) as usize), c, name, 0);
+    with(t, |node| {
+        // The first page is selected once added, and the selected page stays selected.
+        if node.children.len() == 1 {
+            node.value = 0;
+        } else if index <= node.value {
+            node.value += 1;
+        }
+    });
+}
+
+pub unsafe extern "C" fn uiTabDelete(t: *mut uiTab, index: c_int) {
+    detach(t, index);
+    with(t, |node| {
+        if node.children.is_empty() {
+            node.value = -1;
+        } else if index < node.value || node.value as usize == node.children.len() {
+            node.value -= 1;
+        }
+    });
+}
+
+pub unsafe extern "C" fn uiTabNumPages(t: *mut uiTab) -> c_int {
+    num_children(t)
+}
+
+pub unsafe extern "C" fn uiTabMargined(t: *mut uiTab, index: c_int) -> c_int {
+    with(t, |node| match node.children.get(index as usize) {
+        Some(child) => child.margined as c_int,
+        None => user_bug(&format!("index {} out of range", index)),
+    })
+}
+
+pub unsafe extern "C" fn uiTabSetMargined(t: *mut uiTab, index: c_int, margined: c_int) {
+    with(t, |node| match node.children.get_mut(index as usize) {
+        Some(child) => child.margined = margined != 0,
+        None => user_bug(&format!("index {} out of range", index)),
+    })
+}
+
+pub unsafe extern "C" fn uiTabSelected(t: *mut uiTab) -> c_int {
+    with(t, |node| node.value)
+}
+
+pub unsafe extern "C" fn uiTabSetSelected(t: *mut uiTab, index: c_int) {
+    with(t, |node| {
+        if index < 0 || index as usize >= node.children.len() {
+            user_bug(&format!("index {} out of range", index));
+        }
+        node.value = index;
+    })
+}
+
+pub unsafe extern "C" fn uiTabOnSelected(
+    t: *mut uiTab,
+    f: Option<unsafe extern "C" fn(t: *mut uiTab, data: Data)>,
+    data: Data,
+) {
+    set_callback(t, "selected", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewTab() -> *mut uiTab {
+    let tab = create("uiTab", ptr::null());
+    with(tab, |node| node.value = -1);
+    tab
+}
+
+pub unsafe extern "C" fn uiGroupTitle(g: *mut uiGroup) -> *mut c_char {
+    text(g)
+}
+
+pub unsafe extern "C" fn uiGroupSetTitle(g: *mut uiGroup, title: *const c_char) {
+    set_text(g, title)
+}
+
+pub unsafe extern "C" fn uiGroupSetChild(g: *mut uiGroup, c: *mut uiControl) {
+    set_only_child(g, c)
+}
+
+pub unsafe extern "C" fn uiGroupMargined(g: *mut uiGroup) -> c_int {
+    flag(g, "margined")
+}
+
+pub unsafe extern "C" fn uiGroupSetMargined(g: *mut uiGroup, margined: c_int) {
+    set_flag(g, "margined", margined)
+}
+
+pub unsafe extern "C" fn uiNewGroup(title: *const c_char) -> *mut uiGroup {
+    create("uiGroup", title)
+}
+
+/// Creates a control with a value in the range from `min` to `max`, starting at `min`.
+fn create_ranged<T>(kind: &'static str, min: c_int, max: c_int) -> *mut T {
+    let control = create(kind, ptr::null());
+    with(control, |node| {
+        node.range = (min.min(max), min.max(max));
+        node.value = node.range.0;
+    });
+    control
+}
+
+fn set_ranged_value<T>(control: *mut T, value: c_int) {
+    with(control, |node| {
+        node.value = value.max(node.range.0).min(node.range.1)
+    })
+}
+
+pub unsafe extern "C" fn uiSpinboxValue(s: *mut uiSpinbox) -> c_int {
+    with(s, |node| node.value)
+}
+
+pub unsafe extern "C" fn uiSpinboxSetValue(s: *mut uiSpinbox, value: c_int) {
+    set_ranged_value(s, value)
+}
+
+pub unsafe extern "C" fn uiSpinboxOnChanged(
+    s: *mut uiSpinbox,
+    f: Option<unsafe extern "C" fn(s: *mut uiSpinbox, data: Data)>,
+    data: Data,
+) {
+    set_callback(s, "changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewSpinbox(min: c_int, max: c_int) -> *mut uiSpinbox {
+    create_ranged("uiSpinbox", min, max)
+}
+
+pub unsafe extern "C" fn uiSliderValue(s: *mut uiSlider) -> c_int {
+    with(s, |node| node.value)
+}
+
+pub unsafe extern "C" fn uiSliderSetValue(s: *mut uiSlider, value: c_int) {
+    set_ranged_value(s, value)
+}
+
+pub unsafe extern "C" fn uiSliderHasToolTip(s: *mut uiSlider) -> c_int {
+    flag(s, "tooltip")
+}
+
+pub unsafe extern "C" fn uiSliderSetHasToolTip(s: *mut uiSlider, has_tooltip: c_int) {
+    set_flag(s, "tooltip", has_tooltip)
+}
+
+pub unsafe extern "C" fn uiSliderOnChanged(
+    s: *mut uiSlider,
+    f: Option<unsafe extern "C" fn(s: *mut uiSlider, data: Data)>,
+    data: Data,
+) {
+    set_callback(s, "changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiSliderOnReleased(
+    s: *mut uiSlider,
+    f: Option<unsafe extern "C" fn(s: *mut uiSlider, data: Data)>,
+    data: Data,
+) {
+    set_callback(s, "released", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiSliderSetRange(s: *mut uiSlider, min: c_int, max: c_int) {
+    with(s, |node| node.range = (min.min(max), min.max(max)));
+    let value = uiSliderValue(s);
+    set_ranged_value(s, value);
+}
+
+pub unsafe extern "C" fn uiNewSlider(min: c_int, max: c_int) -> *mut uiSlider {
+    let slider = create_ranged("uiSlider", min, max);
+    set_flag(slider, "tooltip", 1);
+    slider
+}
+
+pub unsafe extern "C" fn uiProgressBarValue(p: *mut uiProgressBar) -> c_int {
+    with(p, |node| node.value)
+}
+
+pub unsafe extern "C" fn uiProgressBarSetValue(p: *mut uiProgressBar, value: c_int) {
+    if !(-1..=100).contains(&value) {
+        user_bug(&format!("value {} out of range", value));
+    }
+    with(p, |node| node.value = value)
+}
+
+pub unsafe extern "C" fn uiNewProgressBar() -> *mut uiProgressBar {
+    create("uiProgressBar", ptr::null())
+}
+
+pub unsafe extern "C" fn uiNewHorizontalSeparator() -> *mut uiSeparator {
+    create("uiSeparator", ptr::null())
+}
+
+pub unsafe extern "C" fn uiNewVerticalSeparator() -> *mut uiSeparator {
+    create("uiSeparator", ptr::null())
+}
+
+fn to_owned(text: *const c_char) -> CString {
+    unsafe { CStr::from_ptr(text) }.to_owned()
+}
+
+pub unsafe extern "C" fn uiComboboxAppend(c: *mut uiCombobox, text: *const c_char) {
+    with(c, |node| node.items.push(to_owned(text)))
+}
+
+pub unsafe extern "C" fn uiComboboxInsertAt(c: *mut uiCombobox, index: c_int, text: *const c_char) {
+    with(c, |node| {
+        if index < 0 || index as usize > node.items.len() {
+            user_bug(&format!("index {} out of range", index));
+        }
+        node.items.insert(index as usize, to_owned(text));
+        if node.value >= index {
+            node.value += 1;
+        }
+    })
+}
+
+pub unsafe extern "C" fn uiComboboxDelete(c: *mut uiCombobox, index: c_int) {
+    with(c, |node| {
+        if index < 0 || index as usize >= node.items.len() {
+            user_bug(&format!("index {} out of range", index));
+        }
+        node.items.remove(index as usize);
+        if node.value == index {
+            node.value = -1;
+        } else if node.value > index {
+            node.value -= 1;
+        }
+    })
+}
+
+pub unsafe extern "C" fn uiComboboxClear(c: *mut uiCombobox) {
+    with(c, |node| {
+        node.items.clear();
+        node.value = -1;
+    })
+}
+
+pub unsafe extern "C" fn uiComboboxNumItems(c: *mut uiCombobox) -> c_int {
+    with(c, |node| node.items.len() as c_int)
+}
+
+pub unsafe extern "C" fn uiComboboxSelected(c: *mut uiCombobox) -> c_int {
+    with(c, |node| node.value)
+}
+
+pub unsafe extern "C" fn uiComboboxSetSelected(c: *mut uiCombobox, index: c_int) {
+    with(c, |node| {
+        if index < -1 || index >= node.items.len() as c_int {
+            user_bug(&format!("index {} out of range", index));
+        }
+        node.value = index;
+    })
+}
+
+pub unsafe extern "C" fn uiComboboxOnSelected(
+    c: *mut uiCombobox,
+    f: Option<unsafe extern "C" fn(c: *mut uiCombobox, data: Data)>,
+    data: Data,
+) {
+    set_callback(c, "selected", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewCombobox() -> *mut uiCombobox {
+    let combobox = create("uiCombobox", ptr::null());
+    with(combobox, |node| node.value = -1);
+    combobox
+}
+
+pub unsafe extern "C" fn uiEditableComboboxAppend(c: *mut uiEditableCombobox, text: *const c_char) {
+    with(c, |node| node.items.push(to_owned(text)))
+}
+
+pub unsafe extern "C" fn uiEditableComboboxText(c: *mut uiEditableCombobox) -> *mut c_char {
+    text(c)
+}
+
+pub unsafe extern "C" fn uiEditableComboboxSetText(
+    c: *mut uiEditableCombobox,
+    text: *const c_char,
+) {
+    set_text(c, text)
+}
+
+pub unsafe extern "C" fn uiEditableComboboxOnChanged(
+    c: *mut uiEditableCombobox,
+    f: Option<unsafe extern "C" fn(c: *mut uiEditableCombobox, data: Data)>,
+    data: Data,
+) {
+    set_callback(c, "changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewEditableCombobox() -> *mut uiEditableCombobox {
+    create("uiEditableCombobox", ptr::null())
+}
+
+pub unsafe extern "C" fn uiRadioButtonsAppend(r: *mut uiRadioButtons, text: *const c_char) {
+    with(r, |node| node.items.push(to_owned(text)))
+}
+
+pub unsafe extern "C" fn uiRadioButtonsSelected(r: *mut uiRadioButtons) -> c_int {
+    with(r, |node| node.value)
+}
+
+pub unsafe extern "C" fn uiRadioButtonsSetSelected(r: *mut uiRadioButtons, index: c_int) {
+    with(r, |node| {
+        if index < -1 || index >= node.items.len() as c_int {
+            user_bug(&format!("index {} out of range", index));
+        }
+        node.value = index;
+    })
+}
+
+pub unsafe extern "C" fn uiRadioButtonsOnSelected(
+    r: *mut uiRadioButtons,
+    f: Option<unsafe extern "C" fn(r: *mut uiRadioButtons, data: Data)>,
+    data: Data,
+) {
+    set_callback(r, "selected", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewRadioButtons() -> *mut uiRadioButtons {
+    let radio_buttons = create("uiRadioButtons", ptr::null());
+    with(radio_buttons, |node| node.value = -1);
+    radio_buttons
+}
+
+pub unsafe extern "C" fn uiDateTimePickerTime(d: *mut uiDateTimePicker, time: *mut tm) {
+    with(d, |node| *time = node.time)
+}
+
+pub unsafe extern "C" fn uiDateTimePickerSetTime(d: *mut uiDateTimePicker, time: *const tm) {
+    let mut time = *time;
+    // Normalizes the fields, like the real pickers do.
+    libc::mktime(&mut time);
+    with(d, |node| node.time = time)
+}
+
+pub unsafe extern "C" fn uiDateTimePickerOnChanged(
+    d: *mut uiDateTimePicker,
+    f: Option<unsafe extern "C" fn(d: *mut uiDateTimePicker, data: Data)>,
+    data: Data,
+) {
+    set_callback(d, "changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewDateTimePicker() -> *mut uiDateTimePicker {
+    create("uiDateTimePicker", ptr::null())
+}
+
+pub unsafe extern "C" fn uiNewDatePicker() -> *mut uiDateTimePicker {
+    create("uiDateTimePicker", ptr::null())
+}
+
+pub unsafe extern "C" fn uiNewTimePicker() -> *mut uiDateTimePicker {
+    create("uiDateTimePicker", ptr::null())
+}
+
+pub unsafe extern "C" fn uiMultilineEntryText(e: *mut uiMultilineEntry) -> *mut c_char {
+    text(e)
+}
+
+pub unsafe extern "C" fn uiMultilineEntrySetText(e: *mut uiMultilineEntry, text: *const c_char) {
+    set_text(e, text)
+}
+
+pub unsafe extern "C" fn uiMultilineEntryAppend(e: *mut uiMultilineEntry, text: *const c_char) {
+    let appended = CStr::from_ptr(text).to_bytes();
+    with(e, |node| {
+        let mut text = mem::take(&mut node.text).into_bytes();
+        text.extend_from_slice(appended);
+        node.text = CString::new(text).unwrap();
+    })
+}
+
+pub unsafe extern "C" fn uiMultilineEntryOnChanged(
+    e: *mut uiMultilineEntry,
+    f: Option<unsafe extern "C" fn(e: *mut uiMultilineEntry, data: Data)>,
+    data: Data,
+) {
+    set_callback(e, "changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiMultilineEntryReadOnly(e: *mut uiMultilineEntry) -> c_int {
+    flag(e, "read_only")
+}
+
+pub unsafe extern "C" fn uiMultilineEntrySetReadOnly(e: *mut uiMultilineEntry, readonly: c_int) {
+    set_flag(e, "read_only", readonly)
+}
+
+pub unsafe extern "C" fn uiNewMultilineEntry() -> *mut uiMultilineEntry {
+    create("uiMultilineEntry", ptr::null())
+}
+
+pub unsafe extern "C" fn uiNewNonWrappingMultilineEntry() -> *mut uiMultilineEntry {
+    create("uiMultilineEntry", ptr::null())
+}
+
+pub unsafe extern "C" fn uiFontButtonFont(b: *mut uiFontButton, desc: *mut uiFontDescriptor) {
+    with(b, |node| {
+        *desc = uiFontDescriptor {
+            Family: node.text.clone().into_raw(),
+            Size: node.value as f64,
+            Weight: uiTextWeightNormal,
+            Italic: uiTextItalicNormal,
+            Stretch: uiTextStretchNormal,
+        }
+    })
+}
+
+pub unsafe extern "C" fn uiFontButtonOnChanged(
+    b: *mut uiFontButton,
+    f: Option<unsafe extern "C" fn(b: *mut uiFontButton, data: Data)>,
+    data: Data,
+) {
+    set_callback(b, "changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewFontButton() -> *mut uiFontButton {
+    let button = create("uiFontButton", b"Sans\0".as_ptr() as *const c_char);
+    with(button, |node| node.value = 12);
+    button
+}
+
+pub unsafe extern "C" fn uiFreeFontButtonFont(desc: *mut uiFontDescriptor) {
+    drop(CString::from_raw((*desc).Family));
+    (*desc).Family = ptr::null_mut();
+}
+
+pub unsafe extern "C" fn uiColorButtonColor(
+    b: *mut uiColorButton,
+    r: *mut f64,
+    g: *mut f64,
+    bl: *mut f64,
+    a: *mut f64,
+) {
+    with(b, |node| {
+        *r = node.color.0;
+        *g = node.color.1;
+        *bl = node.color.2;
+        *a = node.color.3;
+    })
+}
+
+pub unsafe extern "C" fn uiColorButtonSetColor(
+    b: *mut uiColorButton,
+    r: f64,
+    g: f64,
+    bl: f64,
+    a: f64,
+) {
+    with(b, |node| node.color = (r, g, bl, a))
+}
+
+pub unsafe extern "C" fn uiColorButtonOnChanged(
+    b: *mut uiColorButton,
+    f: Option<unsafe extern "C" fn(b: *mut uiColorButton, data: Data)>,
+    data: Data,
+) {
+    set_callback(b, "changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiNewColorButton() -> *mut uiColorButton {
+    create("uiColorButton", ptr::null())
+}
+
+pub unsafe extern "C" fn uiFormAppend(
+    f: *mut uiForm,
+    label: *const c_char,
+    c: *mut uiControl,
+    stretchy: c_int,
+) {
+    append_child(f, c, label, stretchy)
+}
+
+pub unsafe extern "C" fn uiFormNumChildren(f: *mut uiForm) -> c_int {
+    num_children(f)
+}
+
+pub unsafe extern "C" fn uiFormDelete(f: *mut uiForm, index: c_int) {
+    detach(f, index)
+}
+
+pub unsafe extern "C" fn uiFormPadded(f: *mut uiForm) -> c_int {
+    flag(f, "padded")
+}
+
+pub unsafe extern "C" fn uiFormSetPadded(f: *mut uiForm, padded: c_int) {
+    set_flag(f, "padded", padded)
+}
+
+pub unsafe extern "C" fn uiNewForm() -> *mut uiForm {
+    create("uiForm", ptr::null())
+}
+
+// The layout within a grid is not tracked, only the order in which children were added.
+
+pub unsafe extern "C" fn uiGridAppend(
+    g: *mut uiGrid,
+    c: *mut uiControl,
+    _left: c_int,
+    _top: c_int,
+    _xspan: c_int,
+    _yspan: c_int,
+    hexpand: c_int,
+    _halign: uiAlign,
+    vexpand: c_int,
+    _valign: uiAlign,
+) {
+    append_child(g, c, ptr::null(), hexpand | vexpand)
+}
+
+pub unsafe extern "C" fn uiGridInsertAt(
+    g: *mut uiGrid,
+    c: *mut uiControl,
+    existing: *mut uiControl,
+    _at: uiAt,
+    _xspan: c_int,
+    _yspan: c_int,
+    hexpand: c_int,
+    _halign: uiAlign,
+    vexpand: c_int,
+    _valign: uiAlign,
+) {
+    let index = with(g, |node| {
+        node.children
+            .iter()
+            .position(|child| child.control == existing)
+    });
+    match index {
+        Some(index) => insert_child(g, Some(index + 1), c, ptr::null(), hexpand | vexpand),
+        None => user_bug("the existing control is not a child of the grid"),
+    }
+}
+
+pub unsafe extern "C" fn uiGridPadded(g: *mut uiGrid) -> c_int {
+    flag(g, "padded")
+}
+
+pub unsafe extern "C" fn uiGridSetPadded(g: *mut uiGrid, padded: c_int) {
+    set_flag(g, "padded", padded)
+}
+
+pub unsafe extern "C" fn uiNewGrid() -> *mut uiGrid {
+    create("uiGrid", ptr::null())
+}
@@ -0,0 +1,71 @@
+//! Standard dialogs. File dialogs return the responses queued with
+//! [`respond_to_file_dialog`](../fn.respond_to_file_dialog.html), message boxes are recorded.
+
+use mock::controls::with;
+use mock::types::*;
+use mock::MessageBox;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+thread_local! {
+    pub(crate) static RESPONSES: RefCell<VecDeque<Option<CString>>> = const { RefCell::new(VecDeque::new()) };
+    pub(crate) static MESSAGES: RefCell<Vec<MessageBox>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(crate) fn clear() {
+    RESPONSES.with(|responses| responses.borrow_mut().clear());
+    MESSAGES.with(|messages| messages.borrow_mut().clear());
+}
+
+/// Returns the next queued response, or `NULL` as if the dialog was cancelled.
+fn respond(parent: *mut uiWindow) -> *mut c_char {
+    with(parent, |_| ());
+    let response = RESPONSES.with(|responses| responses.borrow_mut().pop_front());
+    match response {
+        Some(Some(path)) => path.into_raw(),
+        _ => ptr::null_mut(),
+    }
+}
+
+fn record(parent: *mut uiWindow, title: *const c_char, description: *const c_char, error: bool) {
+    with(parent, |_| ());
+    let message = unsafe {
+        MessageBox {
+            title: CStr::from_ptr(title).to_string_lossy().into_owned(),
+            description: CStr::from_ptr(description).to_string_lossy().into_owned(),
+            error,
+        }
+    };
+    MESSAGES.with(|messages| messages.borrow_mut().push(message));
+}
+
+pub unsafe extern "C" fn uiOpenFile(parent: *mut uiWindow) -> *mut c_char {
+    respond(parent)
+}
+
+pub unsafe extern "C" fn uiOpenFolder(parent: *mut uiWindow) -> *mut c_char {
+    respond(parent)
+}
+
+pub unsafe extern "C" fn uiSaveFile(parent: *mut uiWindow) -> *mut c_char {
+    respond(parent)
+}
+
+pub unsafe extern "C" fn uiMsgBox(
+    parent: *mut uiWindow,
+    title: *const c_char,
+    description: *const c_char,
+) {
+    record(parent, title, description, false)
+}
+
+pub unsafe extern "C" fn uiMsgBoxError(
+    parent: *mut uiWindow,
+    title: *const c_char,
+    description: *const c_char,
+) {
+    record(parent, title, description, true)
+}
@@ -0,0 +1,279 @@
+//! Areas and drawing. Nothing is rendered, but paths and matrices behave as documented.
+
+use mock::controls::{create, with};
+use mock::types::*;
+use mock::user_bug;
+use std::os::raw::c_int;
+
+struct Path {
+    ended: bool,
+}
+
+fn path<'a>(p: *mut uiDrawPath) -> &'a mut Path {
+    unsafe { &mut *(p as *mut Path) }
+}
+
+fn check_open(p: *mut uiDrawPath) {
+    if path(p).ended {
+        user_bug("cannot modify a path which was ended already");
+    }
+}
+
+pub unsafe extern "C" fn uiAreaSetSize(a: *mut uiArea, width: c_int, height: c_int) {
+    with(a, |node| node.size = (width, height))
+}
+
+pub unsafe extern "C" fn uiAreaQueueRedrawAll(a: *mut uiArea) {
+    with(a, |_| ())
+}
+
+pub unsafe extern "C" fn uiAreaScrollTo(a: *mut uiArea, x: f64, y: f64, _width: f64, _height: f64) {
+    with(a, |node| node.position = (x as c_int, y as c_int))
+}
+
+pub unsafe extern "C" fn uiNewArea(handler: *mut uiAreaHandler) -> *mut uiArea {
+    let area = create("uiArea", ::std::ptr::null());
+    with(area, |node| node.area_handler = handler);
+    area
+}
+
+pub unsafe extern "C" fn uiNewScrollingArea(
+    handler: *mut uiAreaHandler,
+    width: c_int,
+    height: c_int,
+) -> *mut uiArea {
+    let area = uiNewArea(handler);
+    uiAreaSetSize(area, width, height);
+    area
+}
+
+pub unsafe extern "C" fn uiDrawNewPath(_fill_mode: uiDrawFillMode) -> *mut uiDrawPath {
+    Box::into_raw(Box::new(Path { ended: false })) as *mut uiDrawPath
+}
+
+pub unsafe extern "C" fn uiDrawFreePath(p: *mut uiDrawPath) {
+    drop(Box::from_raw(p as *mut Path));
+}
+
+pub unsafe extern "C" fn uiDrawPathNewFigure(p: *mut uiDrawPath, _x: f64, _y: f64) {
+    check_open(p)
+}
+
+pub unsafe extern "C" fn uiDrawPathNewFigureWithArc(
+    p: *mut uiDrawPath,
+    _x_center: f64,
+    _y_center: f64,
+    _radius: f64,
+    _start_angle: f64,
+    _sweep: f64,
+    _negative: c_int,
+) {
+    check_open(p)
+}
+
+pub unsafe extern "C" fn uiDrawPathLineTo(p: *mut uiDrawPath, _x: f64, _y: f64) {
+    check_open(p)
+}
+
+pub unsafe extern "C" fn uiDrawPathArcTo(
+    p: *mut uiDrawPath,
+    _x_center: f64,
+    _y_center: f64,
+    _radius: f64,
+    _start_angle: f64,
+    _sweep: f64,
+    _negative: c_int,
+) {
+    check_open(p)
+}
+
+pub unsafe extern "C" fn uiDrawPathBezierTo(
+    p: *mut uiDrawPath,
+    _c1x: f64,
+    _c1y: f64,
+    _c2x: f64,
+    _c2y: f64,
+    _end_x: f64,
+    _end_y: f64,
+) {
+    check_open(p)
+}
+
+pub unsafe extern "C" fn uiDrawPathCloseFigure(p: *mut uiDrawPath) {
+    check_open(p)
+}
+
+pub unsafe extern "C" fn uiDrawPathAddRectangle(
+    p: *mut uiDrawPath,
+    _x: f64,
+    _y: f64,
+    _width: f64,
+    _height: f64,
+) {
+    check_open(p)
+}
+
+pub unsafe extern "C" fn uiDrawPathEnded(p: *mut uiDrawPath) -> c_int {
+    path(p).ended as c_int
+}
+
+pub unsafe extern "C" fn uiDrawPathEnd(p: *mut uiDrawPath) {
+    path(p).ended = true;
+}
+
+fn check_ended(p: *mut uiDrawPath) {
+    if !path(p).ended {
+        user_bug("cannot draw a path which was not ended");
+    }
+}
+
+pub unsafe extern "C" fn uiDrawStroke(
+    _c: *mut uiDrawContext,
+    p: *mut uiDrawPath,
+    _b: *mut uiDrawBrush,
+    _params: *mut uiDrawStrokeParams,
+) {
+    check_ended(p)
+}
+
+pub unsafe extern "C" fn uiDrawFill(
+    _c: *mut uiDrawContext,
+    p: *mut uiDrawPath,
+    _b: *mut uiDrawBrush,
+) {
+    check_ended(p)
+}
+
+pub unsafe extern "C" fn uiDrawTransform(_c: *mut uiDrawContext, _m: *mut uiDrawMatrix) {}
+
+pub unsafe extern "C" fn uiDrawClip(_c: *mut uiDrawContext, p: *mut uiDrawPath) {
+    check_ended(p)
+}
+
+pub unsafe extern "C" fn uiDrawSave(_c: *mut uiDrawContext) {}
+
+pub unsafe extern "C" fn uiDrawRestore(_c: *mut uiDrawContext) {}
+
+// Matrices are applied to row vectors, as in `[x y 1] * M`.
+
+pub unsafe extern "C" fn uiDrawMatrixSetIdentity(m: *mut uiDrawMatrix) {
+    *m = uiDrawMatrix {
+        M11: 1.0,
+        M12: 0.0,
+        M21: 0.0,
+        M22: 1.0,
+        M31: 0.0,
+        M32: 0.0,
+    };
+}
+
+fn identity() -> uiDrawMatrix {
+    let mut m = unsafe { ::std::mem::zeroed() };
+    unsafe { uiDrawMatrixSetIdentity(&mut m) };
+    m
+}
+
+pub unsafe extern "C" fn uiDrawMatrixTranslate(m: *mut uiDrawMatrix, x: f64, y: f64) {
+    let mut n = identity();
+    n.M31 = x;
+    n.M32 = y;
+    uiDrawMatrixMultiply(m, &mut n);
+}
+
+pub unsafe extern "C" fn uiDrawMatrixScale(
+    m: *mut uiDrawMatrix,
+    x_center: f64,
+    y_center: f64,
+    x: f64,
+    y: f64,
+) {
+    let mut n = identity();
+    n.M11 = x;
+    n.M22 = y;
+    n.M31 = x_center - x * x_center;
+    n.M32 = y_center - y * y_center;
+    uiDrawMatrixMultiply(m, &mut n);
+}
+
+pub unsafe extern "C" fn uiDrawMatrixRotate(m: *mut uiDrawMatrix, x: f64, y: f64, amount: f64) {
+    let (sin, cos) = amount.sin_cos();
+    let mut n = identity();
+    n.M11 = cos;
+    n.M12 = sin;
+    n.M21 = -sin;
+    n.M22 = cos;
+    n.M31 = x - x * cos + y * sin;
+    n.M32 = y - x * sin - y * cos;
+    uiDrawMatrixMultiply(m, &mut n);
+}
+
+pub unsafe extern "C" fn uiDrawMatrixSkew(
+    m: *mut uiDrawMatrix,
+    x: f64,
+    y: f64,
+    x_amount: f64,
+    y_amount: f64,
+) {
+    let mut n = identity();
+    n.M12 = y_amount.tan();
+    n.M21 = x_amount.tan();
+    n.M31 = -y * n.M21;
+    n.M32 = -x * n.M12;
+    uiDrawMatrixMultiply(m, &mut n);
+}
+
+pub unsafe extern "C" fn uiDrawMatrixMultiply(dest: *mut uiDrawMatrix, src: *mut uiDrawMatrix) {
+    let a = *dest;
+    let b = *src;
+    *dest = uiDrawMatrix {
+        M11: a.M11 * b.M11 + a.M12 * b.M21,
+        M12: a.M11 * b.M12 + a.M12 * b.M22,
+        M21: a.M21 * b.M11 + a.M22 * b.M21,
+        M22: a.M21 * b.M12 + a.M22 * b.M22,
+        M31: a.M31 * b.M11 + a.M32 * b.M21 + b.M31,
+        M32: a.M31 * b.M12 + a.M32 * b.M22 + b.M32,
+    };
+}
+
+fn determinant(m: &uiDrawMatrix) -> f64 {
+    m.M11 * m.M22 - m.M12 * m.M21
+}
+
+pub unsafe extern "C" fn uiDrawMatrixInvertible(m: *mut uiDrawMatrix) -> c_int {
+    (determinant(&*m) != 0.0) as c_int
+}
+
+pub unsafe extern "C" fn uiDrawMatrixInvert(m: *mut uiDrawMatrix) -> c_int {
+    let a = *m;
+    let det = determinant(&a);
+    if det == 0.0 {
+        return 0;
+    }
+    *m = uiDrawMatrix {
+        M11: a.M22 / det,
+        M12: -a.M12 / det,
+        M21: -a.M21 / det,
+        M22: a.M11 / det,
+        M31: (a.M21 * a.M32 - a.M22 * a.M31) / det,
+        M32: (a.M12 * a.M31 - a.M11 * a.M32) / det,
+    };
+    1
+}
+
+pub unsafe extern "C" fn uiDrawMatrixTransformPoint(
+    m: *mut uiDrawMatrix,
+    x: *mut f64,
+    y: *mut f64,
+) {
+    let m = &*m;
+    let (px, py) = (*x, *y);
+    *x = px * m.M11 + py * m.M21 + m.M31;
+    *y = px * m.M12 + py * m.M22 + m.M32;
+}
+
+pub unsafe extern "C" fn uiDrawMatrixTransformSize(m: *mut uiDrawMatrix, x: *mut f64, y: *mut f64) {
+    let m = &*m;
+    let (px, py) = (*x, *y);
+    *x = px * m.M11 + py * m.M21;
+    *y = px * m.M12 + py * m.M22;
+}
@@ -0,0 +1,193 @@
+//! Initialization and the event loop.
+//!
+//! Functions queued with `uiQueueMain` may come from any thread and are kept in a global queue,
+//! everything else belongs to the thread which called `uiInit`. Timers run on the wall clock.
+
+use mock::types::*;
+use mock::{controls, dialogs, menus};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+struct Queued {
+    f: unsafe extern "C" fn(data: Data),
+    data: Data,
+}
+
+// The data is only ever used on the GUI thread, it merely passes through the queue.
+unsafe impl Send for Queued {}
+
+struct Timer {
+    due: Instant,
+    interval: Duration,
+    f: unsafe extern "C" fn(data: Data) -> c_int,
+    data: Data,
+}
+
+static QUEUE: Mutex<VecDeque<Queued>> = Mutex::new(VecDeque::new());
+static QUEUED: Condvar = Condvar::new();
+
+thread_local! {
+    static INITIALIZED: Cell<bool> = const { Cell::new(false) };
+    static QUIT: Cell<bool> = const { Cell::new(false) };
+    static TIMERS: RefCell<Vec<Timer>> = const { RefCell::new(Vec::new()) };
+    static SHOULD_QUIT: Cell<Option<(unsafe extern "C" fn(data: Data) -> c_int, Data)>> =
+        const { Cell::new(None) };
+}
+
+fn queue() -> MutexGuard<'static, VecDeque<Queued>> {
+    QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Runs the functions which were queued before the call. Returns `false` if there were none.
+unsafe fn run_queued() -> bool {
+    let count = queue().len();
+    for _ in 0..count {
+        // Popped one at a time, as the functions may queue more.
+        let queued = queue().pop_front();
+        if let Some(queued) = queued {
+            (queued.f)(queued.data);
+        }
+    }
+    count > 0
+}
+
+/// Runs the timers which are due. Returns `false` if there were none.
+unsafe fn run_timers() -> bool {
+    let now = Instant::now();
+    let due: Vec<Timer> = TIMERS.with(|timers| {
+        let mut timers = timers.borrow_mut();
+        let (due, pending) = timers.drain(..).partition(|timer| timer.due <= now);
+        *timers = pending;
+        due
+    });
+    let ran = !due.is_empty();
+    for mut timer in due {
+        if (timer.f)(timer.data) != 0 {
+            timer.due = Instant::now() + timer.interval;
+            TIMERS.with(|timers| timers.borrow_mut().push(timer));
+        }
+    }
+    ran
+}
+
+fn next_timer() -> Option<Instant> {
+    TIMERS.with(|timers| timers.borrow().iter().map(|timer| timer.due).min())
+}
+
+/// Handles pending events, waiting for one if `wait` is set. Returns `false` once `uiQuit` was
+/// called.
+unsafe fn step(wait: bool) -> bool {
+    loop {
+        if QUIT.with(|quit| quit.replace(false)) {
+            return false;
+        }
+        let ran = run_queued() | run_timers();
+        if ran || !wait {
+            return !QUIT.with(|quit| quit.replace(false));
+        }
+
+        let queue = queue();
+        if !queue.is_empty() {
+            continue;
+        }
+        // Like the real event loop, this waits forever if nothing is left to happen.
+        match next_timer() {
+            Some(due) => {
+                let timeout = due.saturating_duration_since(Instant::now());
+                drop(QUEUED.wait_timeout(queue, timeout));
+            }
+            None => drop(QUEUED.wait(queue)),
+        }
+    }
+}
+
+pub(crate) fn is_initialized() -> bool {
+    INITIALIZED.with(|initialized| initialized.get())
+}
+
+/// Asks the handler set with `uiOnShouldQuit` whether to quit.
+pub(crate) unsafe fn should_quit() -> bool {
+    match SHOULD_QUIT.with(|should_quit| should_quit.get()) {
+        Some((f, data)) => f(data) != 0,
+        None => true,
+    }
+}
+
+pub unsafe extern "C" fn uiInit(_options: *mut uiInitOptions) -> *const c_char {
+    if is_initialized() {
+        return CString::new("libui is already initialized")
+            .unwrap()
+            .into_raw();
+    }
+    INITIALIZED.with(|initialized| initialized.set(true));
+    std::ptr::null()
+}
+
+pub unsafe extern "C" fn uiUninit() {
+    controls::destroy_all();
+    menus::free_all();
+    dialogs::clear();
+    TIMERS.with(|timers| timers.borrow_mut().clear());
+    queue().clear();
+    SHOULD_QUIT.with(|should_quit| should_quit.set(None));
+    QUIT.with(|quit| quit.set(false));
+    INITIALIZED.with(|initialized| initialized.set(false));
+}
+
+pub unsafe extern "C" fn uiFreeInitError(err: *const c_char) {
+    drop(CString::from_raw(err as *mut c_char));
+}
+
+pub unsafe extern "C" fn uiMain() {
+    while step(true) {}
+}
+
+pub unsafe extern "C" fn uiMainSteps() {}
+
+pub unsafe extern "C" fn uiMainStep(wait: c_int) -> c_int {
+    step(wait != 0) as c_int
+}
+
+pub unsafe extern "C" fn uiQuit() {
+    QUIT.with(|quit| quit.set(true));
+}
+
+pub unsafe extern "C" fn uiQueueMain(f: Option<unsafe extern "C" fn(data: Data)>, data: Data) {
+    if let Some(f) = f {
+        queue().push_back(Queued { f, data });
+        QUEUED.notify_all();
+    }
+}
+
+pub unsafe extern "C" fn uiTimer(
+    milliseconds: c_int,
+    f: Option<unsafe extern "C" fn(data: Data) -> c_int>,
+    data: Data,
+) {
+    if let Some(f) = f {
+        let interval = Duration::from_millis(milliseconds.max(0) as u64);
+        TIMERS.with(|timers| {
+            timers.borrow_mut().push(Timer {
+                due: Instant::now() + interval,
+                interval,
+                f,
+                data,
+            })
+        });
+    }
+}
+
+pub unsafe extern "C" fn uiOnShouldQuit(
+    f: Option<unsafe extern "C" fn(data: Data) -> c_int>,
+    data: Data,
+) {
+    SHOULD_QUIT.with(|should_quit| should_quit.set(f.map(|f| (f, data))));
+}
+
+pub unsafe extern "C" fn uiFreeText(text: *mut c_char) {
+    drop(CString::from_raw(text));
+}
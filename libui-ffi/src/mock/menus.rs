@@ -0,0 +1,180 @@
+//! Menus and their items. Like in libui, they live until uninitializing.
+
+use mock::main::should_quit;
+use mock::types::*;
+use mock::user_bug;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum ItemKind {
+    Normal,
+    Check,
+    Quit,
+    Preferences,
+    About,
+}
+
+pub(crate) struct Item {
+    pub name: CString,
+    pub kind: ItemKind,
+    pub enabled: bool,
+    pub checked: bool,
+    pub on_clicked: Option<(
+        unsafe extern "C" fn(*mut uiMenuItem, *mut uiWindow, Data),
+        Data,
+    )>,
+}
+
+pub(crate) struct Menu {
+    pub name: CString,
+    pub items: Vec<*mut uiMenuItem>,
+}
+
+thread_local! {
+    static MENUS: RefCell<Vec<*mut uiMenu>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(crate) fn item<'a>(m: *mut uiMenuItem) -> &'a mut Item {
+    unsafe { &mut *(m as *mut Item) }
+}
+
+pub(crate) fn menu<'a>(m: *mut uiMenu) -> &'a mut Menu {
+    unsafe { &mut *(m as *mut Menu) }
+}
+
+/// Returns all menus, in the order they were created.
+pub(crate) fn menus() -> Vec<*mut uiMenu> {
+    MENUS.with(|menus| menus.borrow().clone())
+}
+
+/// Clicks the item while `window` is active, as the user would.
+pub(crate) unsafe fn click(m: *mut uiMenuItem, window: *mut uiWindow) {
+    let item = item(m);
+    if !item.enabled {
+        return;
+    }
+    match item.kind {
+        ItemKind::Check => item.checked = !item.checked,
+        ItemKind::Quit => {
+            if should_quit() {
+                ::mock::main::uiQuit();
+            }
+            return;
+        }
+        _ => {}
+    }
+    if let Some((f, data)) = item.on_clicked {
+        f(m, window, data);
+    }
+}
+
+pub(crate) fn free_all() {
+    let menus = MENUS.with(|menus| menus.replace(Vec::new()));
+    for m in menus {
+        let m = unsafe { Box::from_raw(m as *mut Menu) };
+        for item in m.items {
+            drop(unsafe { Box::from_raw(item as *mut Item) });
+        }
+    }
+}
+
+fn append(m: *mut uiMenu, name: &CStr, kind: ItemKind) -> *mut uiMenuItem {
+    let item = Box::into_raw(Box::new(Item {
+        name: name.to_owned(),
+        kind,
+        enabled: true,
+        checked: false,
+        on_clicked: None,
+    })) as *mut uiMenuItem;
+    menu(m).items.push(item);
+    item
+}
+
+/// Appends an item of which there may only be one, as libui enforces.
+fn append_unique(m: *mut uiMenu, name: &CStr, kind: ItemKind) -> *mut uiMenuItem {
+    let exists = menus()
+        .into_iter()
+        .flat_map(|m| menu(m).items.clone())
+        .any(|i| item(i).kind == kind);
+    if exists {
+        user_bug(&format!("cannot add more than one {:?} item", name));
+    }
+    append(m, name, kind)
+}
+
+pub unsafe extern "C" fn uiMenuItemEnable(m: *mut uiMenuItem) {
+    item(m).enabled = true;
+}
+
+pub unsafe extern "C" fn uiMenuItemDisable(m: *mut uiMenuItem) {
+    item(m).enabled = false;
+}
+
+pub unsafe extern "C" fn uiMenuItemOnClicked(
+    m: *mut uiMenuItem,
+    f: Option<unsafe extern "C" fn(sender: *mut uiMenuItem, window: *mut uiWindow, data: Data)>,
+    data: Data,
+) {
+    if item(m).kind == ItemKind::Quit {
+        user_bug("cannot set a click handler on the Quit item, use uiOnShouldQuit() instead");
+    }
+    item(m).on_clicked = f.map(|f| (f, data));
+}
+
+pub unsafe extern "C" fn uiMenuItemChecked(m: *mut uiMenuItem) -> c_int {
+    item(m).checked as c_int
+}
+
+pub unsafe extern "C" fn uiMenuItemSetChecked(m: *mut uiMenuItem, checked: c_int) {
+    item(m).checked = checked != 0;
+}
+
+pub unsafe extern "C" fn uiMenuAppendItem(m: *mut uiMenu, name: *const c_char) -> *mut uiMenuItem {
+    append(m, CStr::from_ptr(name), ItemKind::Normal)
+}
+
+pub unsafe extern "C" fn uiMenuAppendCheckItem(
+    m: *mut uiMenu,
+    name: *const c_char,
+) -> *mut uiMenuItem {
+    append(m, CStr::from_ptr(name), ItemKind::Check)
+}
+
+pub unsafe extern "C" fn uiMenuAppendQuitItem(m: *mut uiMenu) -> *mut uiMenuItem {
+    append_unique(
+        m,
+        CStr::from_bytes_with_nul(b"Quit\0").unwrap(),
+        ItemKind::Quit,
+    )
+}
+
+pub unsafe extern "C" fn uiMenuAppendPreferencesItem(m: *mut uiMenu) -> *mut uiMenuItem {
+    append_unique(
+        m,
+        CStr::from_bytes_with_nul(b"Preferences\0").unwrap(),
+        ItemKind::Preferences,
+    )
+}
+
+pub unsafe extern "C" fn uiMenuAppendAboutItem(m: *mut uiMenu) -> *mut uiMenuItem {
+    append_unique(
+        m,
+        CStr::from_bytes_with_nul(b"About\0").unwrap(),
+        ItemKind::About,
+    )
+}
+
+pub unsafe extern "C" fn uiMenuAppendSeparator(m: *mut uiMenu) {
+    menu(m);
+}
+
+pub unsafe extern "C" fn uiNewMenu(name: *const c_char) -> *mut uiMenu {
+    let m = Box::into_raw(Box::new(Menu {
+        name: CStr::from_ptr(name).to_owned(),
+        items: Vec::new(),
+    })) as *mut uiMenu;
+    MENUS.with(|menus| menus.borrow_mut().push(m));
+    m
+}
@@ -0,0 +1,306 @@
+//! An in-memory implementation of the `libui-ng` API, enabled with the `mock` feature.
+//!
+//! It keeps track of controls, their properties, children and callbacks, so that applications
+//! can be tested without a display server or GTK. Nothing is drawn, and nothing happens unless
+//! a test makes it happen: the functions in this module act as the user would, by firing
+//! callbacks, clicking menu items or answering dialogs, and let tests inspect the state of the
+//! controls.
+//!
+//! Mistakes which libui reports as bugs in the program, such as destroying a control which still
+//! has a parent, abort the process with a message.
+
+// The functions mirror the C API of libui-ng, which documents their requirements.
+#![allow(clippy::missing_safety_doc)]
+
+pub(crate) mod controls;
+pub(crate) mod dialogs;
+pub(crate) mod draw;
+pub(crate) mod main;
+pub(crate) mod menus;
+pub(crate) mod table;
+pub(crate) mod types;
+pub(crate) mod windows;
+
+use self::types::*;
+use std::ffi::CString;
+use std::os::raw::c_int;
+use std::process;
+
+/// A message box shown with `uiMsgBox` or `uiMsgBoxError`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageBox {
+    pub title: String,
+    pub description: String,
+    /// Whether the message box was shown with `uiMsgBoxError`.
+    pub error: bool,
+}
+
+/// The value of a cell of a table, as returned by its model.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CellValue {
+    String(String),
+    Int(c_int),
+    Color(f64, f64, f64, f64),
+}
+
+/// Reports a bug in the program using libui, and aborts.
+pub fn user_bug(message: &str) -> ! {
+    eprintln!("[libui] You have a bug: {}", message);
+    process::abort();
+}
+
+/// Returns `true` if the pointer is a control which was not destroyed yet.
+pub fn is_alive<T>(control: *mut T) -> bool {
+    controls::is_alive(control)
+}
+
+/// Returns the name of the type of the control, such as `"uiButton"`, or `None` if it was
+/// destroyed.
+pub fn kind<T>(control: *mut T) -> Option<&'static str> {
+    if is_alive(control) {
+        Some(controls::with(control, |node| node.kind))
+    } else {
+        None
+    }
+}
+
+/// Returns the children of a container, in order.
+pub fn children<T>(control: *mut T) -> Vec<*mut uiControl> {
+    controls::with(control, |node| {
+        node.children.iter().map(|child| child.control).collect()
+    })
+}
+
+/// Returns the labels a container shows for its children, such as the names of the pages of a
+/// tab or the labels of a form.
+pub fn labels<T>(control: *mut T) -> Vec<String> {
+    controls::with(control, |node| {
+        node.children
+            .iter()
+            .map(|child| child.label.to_string_lossy().into_owned())
+            .collect()
+    })
+}
+
+/// Returns the text, title or font family of the control.
+pub fn text<T>(control: *mut T) -> String {
+    controls::with(control, |node| node.text.to_string_lossy().into_owned())
+}
+
+/// Returns the items of a combobox or radio buttons.
+pub fn items<T>(control: *mut T) -> Vec<String> {
+    controls::with(control, |node| {
+        node.items
+            .iter()
+            .map(|item| item.to_string_lossy().into_owned())
+            .collect()
+    })
+}
+
+/// Returns the names of the columns of a table.
+pub fn columns(table: *mut uiTable) -> Vec<String> {
+    table::column_names(table)
+}
+
+/// Returns the number of rows of a table, as reported by its model.
+///
+/// # Safety
+/// The model of the table must still be valid.
+pub unsafe fn num_rows(table: *mut uiTable) -> c_int {
+    table::num_rows(table)
+}
+
+/// Returns the value of a cell of a table, as reported by its model.
+///
+/// # Safety
+/// The model of the table must still be valid.
+pub unsafe fn cell_value(table: *mut uiTable, row: c_int, column: c_int) -> CellValue {
+    table::cell_value(table, row, column)
+}
+
+/// Returns whether the children of a box, form or grid expand to take up the available space.
+pub fn stretchy<T>(control: *mut T) -> Vec<bool> {
+    controls::with(control, |node| {
+        node.children.iter().map(|child| child.stretchy).collect()
+    })
+}
+
+/// Returns the windows which were not destroyed yet, oldest first.
+pub fn windows() -> Vec<*mut uiWindow> {
+    controls::windows()
+}
+
+/// Returns `true` if a callback is set for the event, such as `"clicked"`, on the control.
+pub fn has_callback<T>(control: *mut T, event: &str) -> bool {
+    controls::with(control, |node| node.callbacks.contains_key(event))
+}
+
+/// Calls the callback set for the event on the control, if any, as if the user triggered it.
+/// Returns `false` if there is no callback.
+///
+/// The control is not changed, so set its new state before firing the event. This works for all
+/// events with a callback taking only the control, which are `"clicked"`, `"toggled"`,
+/// `"changed"`, `"selected"`, `"released"`, `"selection_changed"`, `"position_changed"`,
+/// `"content_size_changed"` and `"focus_changed"`.
+///
+/// # Safety
+/// The callback is called with the data it was set with, which must still be valid.
+pub unsafe fn fire<T>(control: *mut T, event: &str) -> bool {
+    let callback = controls::with(control, |node| node.callbacks.get(event).cloned());
+    match callback {
+        Some(callback) => {
+            let f: unsafe extern "C" fn(*mut T, Data) = std::mem::transmute(callback.f);
+            f(control, callback.data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Like [`fire`](fn.fire.html), for the events of tables which also pass a row or column,
+/// which are `"row_clicked"`, `"row_double_clicked"` and `"header_clicked"`.
+///
+/// # Safety
+/// The callback is called with the data it was set with, which must still be valid.
+pub unsafe fn fire_with_index(table: *mut uiTable, event: &str, index: c_int) -> bool {
+    let callback = controls::with(table, |node| node.callbacks.get(event).cloned());
+    match callback {
+        Some(callback) => {
+            let f: unsafe extern "C" fn(*mut uiTable, c_int, Data) =
+                std::mem::transmute(callback.f);
+            f(table, index, callback.data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Closes the window as if the user clicked its close button. The window is destroyed if its
+/// `"closing"` callback returns nonzero. Returns `true` if it was destroyed.
+///
+/// # Safety
+/// The callback is called with the data it was set with, which must still be valid.
+pub unsafe fn close(window: *mut uiWindow) -> bool {
+    let callback = controls::callback(window, "closing");
+    let destroy = match callback {
+        Some(callback) => {
+            let f: unsafe extern "C" fn(*mut uiWindow, Data) -> c_int =
+                std::mem::transmute(callback.f);
+            f(window, callback.data) != 0
+        }
+        None => false,
+    };
+    if destroy {
+        controls::uiControlDestroy(window as *mut uiControl);
+    }
+    destroy
+}
+
+/// Returns the item with the given name in the menu with the given name.
+pub fn find_menu_item(menu: &str, item: &str) -> Option<*mut uiMenuItem> {
+    menus::menus()
+        .into_iter()
+        .filter(|&m| menus::menu(m).name.to_string_lossy() == menu)
+        .flat_map(|m| menus::menu(m).items.clone())
+        .find(|&i| menus::item(i).name.to_string_lossy() == item)
+}
+
+/// Clicks the menu item while `window` is active, as the user would. Check items are toggled,
+/// and the Quit item asks the `uiOnShouldQuit` handler before quitting. Nothing happens if the
+/// item is disabled.
+///
+/// # Safety
+/// The callback is called with the data it was set with, which must still be valid.
+pub unsafe fn click_menu_item(item: *mut uiMenuItem, window: *mut uiWindow) {
+    menus::click(item, window)
+}
+
+/// Queues the response of the next file dialog, `None` meaning that it is cancelled.
+/// Without a queued response, file dialogs are cancelled.
+pub fn respond_to_file_dialog(path: Option<&str>) {
+    let path = path.map(|path| CString::new(path).expect("path contains a nul byte"));
+    dialogs::RESPONSES.with(|responses| responses.borrow_mut().push_back(path));
+}
+
+/// Returns the message boxes shown since the last call, oldest first.
+pub fn take_message_boxes() -> Vec<MessageBox> {
+    dialogs::MESSAGES.with(|messages| messages.replace(Vec::new()))
+}
+
+/// Returns `true` between `uiInit` and `uiUninit` on this thread.
+pub fn is_initialized() -> bool {
+    main::is_initialized()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mock::controls::{
+        uiBoxAppend, uiControlDestroy, uiControlParent, uiNewButton, uiNewVerticalBox,
+    };
+    use mock::draw::*;
+    use mock::table::{uiFreeTableModel, uiNewTable, uiNewTableModel};
+    use mock::windows::{uiNewWindow, uiWindowSetChild};
+    use std::ptr;
+
+    #[test]
+    fn destroying_a_window_destroys_its_children() {
+        unsafe {
+            let title = CString::new("Test").unwrap();
+            let window = uiNewWindow(title.as_ptr(), 200, 100, 0);
+            let vbox = uiNewVerticalBox();
+            let button = uiNewButton(title.as_ptr());
+            uiBoxAppend(vbox, button as *mut uiControl, 1);
+            uiWindowSetChild(window, vbox as *mut uiControl);
+
+            assert_eq!(children(vbox), vec![button as *mut uiControl]);
+            assert_eq!(stretchy(vbox), vec![true]);
+            assert_eq!(
+                uiControlParent(button as *mut uiControl),
+                vbox as *mut uiControl
+            );
+            assert_eq!(windows(), vec![window]);
+
+            uiControlDestroy(window as *mut uiControl);
+            assert!(!is_alive(window));
+            assert!(!is_alive(vbox));
+            assert!(!is_alive(button));
+            assert!(windows().is_empty());
+        }
+    }
+
+    #[test]
+    fn table_models_are_freed_after_their_tables() {
+        unsafe {
+            let model = uiNewTableModel(ptr::null_mut());
+            let mut params = uiTableParams {
+                Model: model,
+                RowBackgroundColorModelColumn: -1,
+            };
+            let first = uiNewTable(&mut params);
+            let second = uiNewTable(&mut params);
+            uiControlDestroy(first as *mut uiControl);
+            uiControlDestroy(second as *mut uiControl);
+            // Freeing the model before destroying both tables aborts, like libui does.
+            uiFreeTableModel(model);
+        }
+    }
+
+    #[test]
+    fn inverted_matrix_undoes_the_transformation() {
+        unsafe {
+            let mut m = std::mem::zeroed();
+            uiDrawMatrixSetIdentity(&mut m);
+            uiDrawMatrixTranslate(&mut m, 10.0, -5.0);
+            uiDrawMatrixScale(&mut m, 1.0, 2.0, 3.0, 0.5);
+            uiDrawMatrixRotate(&mut m, 4.0, 4.0, 0.3);
+
+            let (mut x, mut y) = (7.0, 11.0);
+            uiDrawMatrixTransformPoint(&mut m, &mut x, &mut y);
+            assert_eq!(uiDrawMatrixInvert(&mut m), 1);
+            uiDrawMatrixTransformPoint(&mut m, &mut x, &mut y);
+            assert!((x - 7.0).abs() < 1e-9);
+            assert!((y - 11.0).abs() < 1e-9);
+        }
+    }
+}
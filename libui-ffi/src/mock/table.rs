@@ -0,0 +1,356 @@
+//! Tables, their models and values.
+
+use mock::controls::{create, set_callback, with};
+use mock::types::*;
+use mock::{user_bug, CellValue};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::slice;
+
+enum Value {
+    String(CString),
+    Int(c_int),
+    Color(f64, f64, f64, f64),
+}
+
+pub(crate) struct Model {
+    pub handler: *mut uiTableModelHandler,
+    // How many tables use the model, which may not be freed before them.
+    tables: usize,
+}
+
+struct Column {
+    name: CString,
+    width: c_int,
+    sort_indicator: uiSortIndicator,
+}
+
+pub(crate) struct TableState {
+    pub model: *mut uiTableModel,
+    columns: Vec<Column>,
+    selection_mode: uiTableSelectionMode,
+    pub selection: Vec<c_int>,
+}
+
+impl Drop for TableState {
+    fn drop(&mut self) {
+        unsafe { (*(self.model as *mut Model)).tables -= 1 };
+    }
+}
+
+fn value<'a>(v: *const uiTableValue) -> &'a Value {
+    unsafe { &*(v as *const Value) }
+}
+
+fn new_value(v: Value) -> *mut uiTableValue {
+    Box::into_raw(Box::new(v)) as *mut uiTableValue
+}
+
+fn table<R, F: FnOnce(&mut TableState) -> R>(t: *mut uiTable, f: F) -> R {
+    with(t, |node| {
+        f(node.table.as_mut().expect("a table without state"))
+    })
+}
+
+fn column<R, F: FnOnce(&mut Column) -> R>(t: *mut uiTable, index: c_int, f: F) -> R {
+    table(t, |table| match table.columns.get_mut(index as usize) {
+        Some(column) => f(column),
+        None => user_bug(&format!("column {} out of range", index)),
+    })
+}
+
+/// Asks the model of the table for the number of rows.
+pub(crate) unsafe fn num_rows(t: *mut uiTable) -> c_int {
+    let handler = (*(table(t, |table| table.model) as *mut Model)).handler;
+    let num_rows = (*handler).NumRows.expect("model without NumRows");
+    num_rows(handler, table(t, |table| table.model))
+}
+
+/// Asks the model of the table for the value of a cell.
+pub(crate) unsafe fn cell_value(t: *mut uiTable, row: c_int, column: c_int) -> CellValue {
+    let model = table(t, |table| table.model);
+    let handler = (*(model as *mut Model)).handler;
+    let cell_value = (*handler).CellValue.expect("model without CellValue");
+    let v = cell_value(handler, model, row, column);
+    let result = match *value(v) {
+        Value::String(ref s) => CellValue::String(s.to_string_lossy().into_owned()),
+        Value::Int(i) => CellValue::Int(i),
+        Value::Color(r, g, b, a) => CellValue::Color(r, g, b, a),
+    };
+    uiFreeTableValue(v);
+    result
+}
+
+/// Returns the names of the columns of the table.
+pub(crate) fn column_names(t: *mut uiTable) -> Vec<String> {
+    table(t, |table| {
+        table
+            .columns
+            .iter()
+            .map(|column| column.name.to_string_lossy().into_owned())
+            .collect()
+    })
+}
+
+pub unsafe extern "C" fn uiFreeTableValue(v: *mut uiTableValue) {
+    drop(Box::from_raw(v as *mut Value));
+}
+
+pub unsafe extern "C" fn uiTableValueGetType(v: *const uiTableValue) -> uiTableValueType {
+    match *value(v) {
+        Value::String(_) => uiTableValueTypeString,
+        Value::Int(_) => uiTableValueTypeInt,
+        Value::Color(..) => uiTableValueTypeColor,
+    }
+}
+
+pub unsafe extern "C" fn uiNewTableValueString(s: *const c_char) -> *mut uiTableValue {
+    new_value(Value::String(CStr::from_ptr(s).to_owned()))
+}
+
+pub unsafe extern "C" fn uiTableValueString(v: *const uiTableValue) -> *const c_char {
+    match *value(v) {
+        Value::String(ref s) => s.as_ptr(),
+        _ => user_bug("the table value is not a string"),
+    }
+}
+
+pub unsafe extern "C" fn uiNewTableValueInt(i: c_int) -> *mut uiTableValue {
+    new_value(Value::Int(i))
+}
+
+pub unsafe extern "C" fn uiTableValueInt(v: *const uiTableValue) -> c_int {
+    match *value(v) {
+        Value::Int(i) => i,
+        _ => user_bug("the table value is not an int"),
+    }
+}
+
+pub unsafe extern "C" fn uiNewTableValueColor(r: f64, g: f64, b: f64, a: f64) -> *mut uiTableValue {
+    new_value(Value::Color(r, g, b, a))
+}
+
+pub unsafe extern "C" fn uiTableValueColor(
+    v: *const uiTableValue,
+    r: *mut f64,
+    g: *mut f64,
+    b: *mut f64,
+    a: *mut f64,
+) {
+    match *value(v) {
+        Value::Color(red, green, blue, alpha) => {
+            *r = red;
+            *g = green;
+            *b = blue;
+            *a = alpha;
+        }
+        _ => user_bug("the table value is not a color"),
+    }
+}
+
+pub unsafe extern "C" fn uiNewTableModel(handler: *mut uiTableModelHandler) -> *mut uiTableModel {
+    Box::into_raw(Box::new(Model { handler, tables: 0 })) as *mut uiTableModel
+}
+
+pub unsafe extern "C" fn uiFreeTableModel(m: *mut uiTableModel) {
+    if (*(m as *mut Model)).tables != 0 {
+        user_bug("You cannot free a uiTableModel while uiTables are using it.");
+    }
+    drop(Box::from_raw(m as *mut Model));
+}
+
+// Rows are read from the model when needed, so changes need not be tracked.
+
+pub unsafe extern "C" fn uiTableModelRowInserted(_m: *mut uiTableModel, _index: c_int) {}
+
+pub unsafe extern "C" fn uiTableModelRowChanged(_m: *mut uiTableModel, _index: c_int) {}
+
+pub unsafe extern "C" fn uiTableModelRowDeleted(_m: *mut uiTableModel, _index: c_int) {}
+
+fn append_column(t: *mut uiTable, name: *const c_char) {
+    let name = unsafe { CStr::from_ptr(name) }.to_owned();
+    table(t, |table| {
+        table.columns.push(Column {
+            name,
+            width: -1,
+            sort_indicator: uiSortIndicatorNone,
+        })
+    });
+}
+
+pub unsafe extern "C" fn uiTableAppendTextColumn(
+    t: *mut uiTable,
+    name: *const c_char,
+    _text_model_column: c_int,
+    _text_editable_model_column: c_int,
+    _text_params: *mut uiTableTextColumnOptionalParams,
+) {
+    append_column(t, name)
+}
+
+pub unsafe extern "C" fn uiTableAppendCheckboxColumn(
+    t: *mut uiTable,
+    name: *const c_char,
+    _checkbox_model_column: c_int,
+    _checkbox_editable_model_column: c_int,
+) {
+    append_column(t, name)
+}
+
+pub unsafe extern "C" fn uiTableAppendCheckboxTextColumn(
+    t: *mut uiTable,
+    name: *const c_char,
+    _checkbox_model_column: c_int,
+    _checkbox_editable_model_column: c_int,
+    _text_model_column: c_int,
+    _text_editable_model_column: c_int,
+    _text_params: *mut uiTableTextColumnOptionalParams,
+) {
+    append_column(t, name)
+}
+
+pub unsafe extern "C" fn uiTableAppendProgressBarColumn(
+    t: *mut uiTable,
+    name: *const c_char,
+    _progress_model_column: c_int,
+) {
+    append_column(t, name)
+}
+
+pub unsafe extern "C" fn uiTableAppendButtonColumn(
+    t: *mut uiTable,
+    name: *const c_char,
+    _button_model_column: c_int,
+    _button_clickable_model_column: c_int,
+) {
+    append_column(t, name)
+}
+
+pub unsafe extern "C" fn uiTableHeaderVisible(t: *mut uiTable) -> c_int {
+    ::mock::controls::flag(t, "header_visible")
+}
+
+pub unsafe extern "C" fn uiTableHeaderSetVisible(t: *mut uiTable, visible: c_int) {
+    ::mock::controls::set_flag(t, "header_visible", visible)
+}
+
+pub unsafe extern "C" fn uiNewTable(params: *mut uiTableParams) -> *mut uiTable {
+    let t = create("uiTable", ::std::ptr::null());
+    (*((*params).Model as *mut Model)).tables += 1;
+    with(t, |node| {
+        node.table = Some(Box::new(TableState {
+            model: (*params).Model,
+            columns: Vec::new(),
+            selection_mode: uiTableSelectionModeZeroOrOne,
+            selection: Vec::new(),
+        }))
+    });
+    uiTableHeaderSetVisible(t, 1);
+    t
+}
+
+pub unsafe extern "C" fn uiTableOnRowClicked(
+    t: *mut uiTable,
+    f: Option<unsafe extern "C" fn(t: *mut uiTable, row: c_int, data: Data)>,
+    data: Data,
+) {
+    set_callback(t, "row_clicked", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiTableOnRowDoubleClicked(
+    t: *mut uiTable,
+    f: Option<unsafe extern "C" fn(t: *mut uiTable, row: c_int, data: Data)>,
+    data: Data,
+) {
+    set_callback(t, "row_double_clicked", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiTableHeaderSetSortIndicator(
+    t: *mut uiTable,
+    column: c_int,
+    indicator: uiSortIndicator,
+) {
+    self::column(t, column, |column| column.sort_indicator = indicator)
+}
+
+pub unsafe extern "C" fn uiTableHeaderSortIndicator(
+    t: *mut uiTable,
+    column: c_int,
+) -> uiSortIndicator {
+    self::column(t, column, |column| column.sort_indicator)
+}
+
+pub unsafe extern "C" fn uiTableHeaderOnClicked(
+    t: *mut uiTable,
+    f: Option<unsafe extern "C" fn(t: *mut uiTable, column: c_int, data: Data)>,
+    data: Data,
+) {
+    set_callback(t, "header_clicked", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiTableColumnWidth(t: *mut uiTable, column: c_int) -> c_int {
+    self::column(t, column, |column| column.width)
+}
+
+pub unsafe extern "C" fn uiTableColumnSetWidth(t: *mut uiTable, column: c_int, width: c_int) {
+    self::column(t, column, |column| column.width = width)
+}
+
+pub unsafe extern "C" fn uiTableGetSelectionMode(t: *mut uiTable) -> uiTableSelectionMode {
+    table(t, |table| table.selection_mode)
+}
+
+pub unsafe extern "C" fn uiTableSetSelectionMode(t: *mut uiTable, mode: uiTableSelectionMode) {
+    table(t, |table| {
+        table.selection_mode = mode;
+        let allowed = match mode {
+            uiTableSelectionModeNone => 0,
+            uiTableSelectionModeZeroOrOne | uiTableSelectionModeOne => 1,
+            _ => usize::MAX,
+        };
+        table.selection.truncate(allowed);
+    })
+}
+
+pub unsafe extern "C" fn uiTableOnSelectionChanged(
+    t: *mut uiTable,
+    f: Option<unsafe extern "C" fn(t: *mut uiTable, data: Data)>,
+    data: Data,
+) {
+    set_callback(t, "selection_changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiTableGetSelection(t: *mut uiTable) -> *mut uiTableSelection {
+    let rows = table(t, |table| table.selection.clone()).into_boxed_slice();
+    let selection = uiTableSelection {
+        NumRows: rows.len() as c_int,
+        Rows: Box::into_raw(rows) as *mut c_int,
+    };
+    Box::into_raw(Box::new(selection))
+}
+
+pub unsafe extern "C" fn uiTableSetSelection(t: *mut uiTable, selection: *mut uiTableSelection) {
+    let selection = &*selection;
+    let rows = if selection.NumRows > 0 {
+        slice::from_raw_parts(selection.Rows, selection.NumRows as usize).to_vec()
+    } else {
+        Vec::new()
+    };
+    table(t, |table| {
+        // Selecting more rows than the selection mode allows does nothing, as in libui.
+        let allowed = match table.selection_mode {
+            uiTableSelectionModeNone => rows.is_empty(),
+            uiTableSelectionModeZeroOrOne | uiTableSelectionModeOne => rows.len() <= 1,
+            _ => true,
+        };
+        if allowed {
+            table.selection = rows;
+        }
+    })
+}
+
+pub unsafe extern "C" fn uiFreeTableSelection(s: *mut uiTableSelection) {
+    let s = Box::from_raw(s);
+    let rows = slice::from_raw_parts_mut(s.Rows, s.NumRows as usize);
+    drop(Box::from_raw(rows as *mut [c_int]));
+}
@@ -0,0 +1,391 @@
+//! The types and constants of `ui.h`, laid out as bindgen generates them.
+
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+
+macro_rules! opaque_types {
+    ($($name:ident)*) => {
+        $(
+            #[repr(C)]
+            #[derive(Debug, Copy, Clone)]
+            pub struct $name {
+                _unused: [u8; 0],
+            }
+        )*
+    };
+}
+
+opaque_types! {
+    uiWindow uiButton uiBox uiCheckbox uiEntry uiLabel uiTab uiGroup uiSpinbox uiSlider
+    uiProgressBar uiSeparator uiCombobox uiEditableCombobox uiRadioButtons uiDateTimePicker
+    uiMultilineEntry uiMenuItem uiMenu uiArea uiDrawContext uiDrawPath uiFontButton
+    uiColorButton uiForm uiGrid uiImage uiTableValue uiTableModel uiTable uiAttributedString
+    uiDrawTextLayout
+}
+
+macro_rules! enum_constants {
+    ($enum:ident { $($name:ident = $value:expr,)* }) => {
+        pub type $enum = c_uint;
+        $(pub const $name: $enum = $value;)*
+    };
+}
+
+enum_constants!(uiForEach {
+    uiForEachContinue = 0,
+    uiForEachStop = 1,
+});
+
+enum_constants!(uiDrawBrushType {
+    uiDrawBrushTypeSolid = 0,
+    uiDrawBrushTypeLinearGradient = 1,
+    uiDrawBrushTypeRadialGradient = 2,
+    uiDrawBrushTypeImage = 3,
+});
+
+enum_constants!(uiDrawLineCap {
+    uiDrawLineCapFlat = 0,
+    uiDrawLineCapRound = 1,
+    uiDrawLineCapSquare = 2,
+});
+
+enum_constants!(uiDrawLineJoin {
+    uiDrawLineJoinMiter = 0,
+    uiDrawLineJoinRound = 1,
+    uiDrawLineJoinBevel = 2,
+});
+
+enum_constants!(uiDrawFillMode {
+    uiDrawFillModeWinding = 0,
+    uiDrawFillModeAlternate = 1,
+});
+
+pub const uiDrawDefaultMiterLimit: f64 = 10.0;
+
+enum_constants!(uiModifiers {
+    uiModifierCtrl = 1 << 0,
+    uiModifierAlt = 1 << 1,
+    uiModifierShift = 1 << 2,
+    uiModifierSuper = 1 << 3,
+});
+
+enum_constants!(uiExtKey {
+    uiExtKeyEscape = 1,
+    uiExtKeyInsert = 2,
+    uiExtKeyDelete = 3,
+    uiExtKeyHome = 4,
+    uiExtKeyEnd = 5,
+    uiExtKeyPageUp = 6,
+    uiExtKeyPageDown = 7,
+    uiExtKeyUp = 8,
+    uiExtKeyDown = 9,
+    uiExtKeyLeft = 10,
+    uiExtKeyRight = 11,
+    uiExtKeyF1 = 12,
+    uiExtKeyF2 = 13,
+    uiExtKeyF3 = 14,
+    uiExtKeyF4 = 15,
+    uiExtKeyF5 = 16,
+    uiExtKeyF6 = 17,
+    uiExtKeyF7 = 18,
+    uiExtKeyF8 = 19,
+    uiExtKeyF9 = 20,
+    uiExtKeyF10 = 21,
+    uiExtKeyF11 = 22,
+    uiExtKeyF12 = 23,
+    uiExtKeyN0 = 24,
+    uiExtKeyN1 = 25,
+    uiExtKeyN2 = 26,
+    uiExtKeyN3 = 27,
+    uiExtKeyN4 = 28,
+    uiExtKeyN5 = 29,
+    uiExtKeyN6 = 30,
+    uiExtKeyN7 = 31,
+    uiExtKeyN8 = 32,
+    uiExtKeyN9 = 33,
+    uiExtKeyNDot = 34,
+    uiExtKeyNEnter = 35,
+    uiExtKeyNAdd = 36,
+    uiExtKeyNSubtract = 37,
+    uiExtKeyNMultiply = 38,
+    uiExtKeyNDivide = 39,
+});
+
+enum_constants!(uiTextWeight {
+    uiTextWeightMinimum = 0,
+    uiTextWeightThin = 100,
+    uiTextWeightUltraLight = 200,
+    uiTextWeightLight = 300,
+    uiTextWeightBook = 350,
+    uiTextWeightNormal = 400,
+    uiTextWeightMedium = 500,
+    uiTextWeightSemiBold = 600,
+    uiTextWeightBold = 700,
+    uiTextWeightUltraBold = 800,
+    uiTextWeightHeavy = 900,
+    uiTextWeightUltraHeavy = 950,
+    uiTextWeightMaximum = 1000,
+});
+
+enum_constants!(uiTextItalic {
+    uiTextItalicNormal = 0,
+    uiTextItalicOblique = 1,
+    uiTextItalicItalic = 2,
+});
+
+enum_constants!(uiTextStretch {
+    uiTextStretchUltraCondensed = 0,
+    uiTextStretchExtraCondensed = 1,
+    uiTextStretchCondensed = 2,
+    uiTextStretchSemiCondensed = 3,
+    uiTextStretchNormal = 4,
+    uiTextStretchSemiExpanded = 5,
+    uiTextStretchExpanded = 6,
+    uiTextStretchExtraExpanded = 7,
+    uiTextStretchUltraExpanded = 8,
+});
+
+enum_constants!(uiAlign {
+    uiAlignFill = 0,
+    uiAlignStart = 1,
+    uiAlignCenter = 2,
+    uiAlignEnd = 3,
+});
+
+enum_constants!(uiAt {
+    uiAtLeading = 0,
+    uiAtTop = 1,
+    uiAtTrailing = 2,
+    uiAtBottom = 3,
+});
+
+enum_constants!(uiTableValueType {
+    uiTableValueTypeString = 0,
+    uiTableValueTypeImage = 1,
+    uiTableValueTypeInt = 2,
+    uiTableValueTypeColor = 3,
+});
+
+enum_constants!(uiSortIndicator {
+    uiSortIndicatorNone = 0,
+    uiSortIndicatorAscending = 1,
+    uiSortIndicatorDescending = 2,
+});
+
+enum_constants!(uiTableSelectionMode {
+    uiTableSelectionModeNone = 0,
+    uiTableSelectionModeZeroOrOne = 1,
+    uiTableSelectionModeOne = 2,
+    uiTableSelectionModeZeroOrMany = 3,
+});
+
+pub const uiTableModelColumnNeverEditable: c_int = -1;
+pub const uiTableModelColumnAlwaysEditable: c_int = -2;
+
+pub type tm = ::libc::tm;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiInitOptions {
+    pub Size: usize,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiControl {
+    pub Signature: u32,
+    pub OSSignature: u32,
+    pub TypeSignature: u32,
+    pub Destroy: Option<unsafe extern "C" fn(arg1: *mut uiControl)>,
+    pub Handle: Option<unsafe extern "C" fn(arg1: *mut uiControl) -> usize>,
+    pub Parent: Option<unsafe extern "C" fn(arg1: *mut uiControl) -> *mut uiControl>,
+    pub SetParent: Option<unsafe extern "C" fn(arg1: *mut uiControl, arg2: *mut uiControl)>,
+    pub Toplevel: Option<unsafe extern "C" fn(arg1: *mut uiControl) -> c_int>,
+    pub Visible: Option<unsafe extern "C" fn(arg1: *mut uiControl) -> c_int>,
+    pub Show: Option<unsafe extern "C" fn(arg1: *mut uiControl)>,
+    pub Hide: Option<unsafe extern "C" fn(arg1: *mut uiControl)>,
+    pub Enabled: Option<unsafe extern "C" fn(arg1: *mut uiControl) -> c_int>,
+    pub Enable: Option<unsafe extern "C" fn(arg1: *mut uiControl)>,
+    pub Disable: Option<unsafe extern "C" fn(arg1: *mut uiControl)>,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiAreaHandler {
+    pub Draw: Option<
+        unsafe extern "C" fn(
+            arg1: *mut uiAreaHandler,
+            arg2: *mut uiArea,
+            arg3: *mut uiAreaDrawParams,
+        ),
+    >,
+    pub MouseEvent: Option<
+        unsafe extern "C" fn(
+            arg1: *mut uiAreaHandler,
+            arg2: *mut uiArea,
+            arg3: *mut uiAreaMouseEvent,
+        ),
+    >,
+    pub MouseCrossed:
+        Option<unsafe extern "C" fn(arg1: *mut uiAreaHandler, arg2: *mut uiArea, left: c_int)>,
+    pub DragBroken: Option<unsafe extern "C" fn(arg1: *mut uiAreaHandler, arg2: *mut uiArea)>,
+    pub KeyEvent: Option<
+        unsafe extern "C" fn(
+            arg1: *mut uiAreaHandler,
+            arg2: *mut uiArea,
+            arg3: *mut uiAreaKeyEvent,
+        ) -> c_int,
+    >,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiAreaDrawParams {
+    pub Context: *mut uiDrawContext,
+    pub AreaWidth: f64,
+    pub AreaHeight: f64,
+    pub ClipX: f64,
+    pub ClipY: f64,
+    pub ClipWidth: f64,
+    pub ClipHeight: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiDrawMatrix {
+    pub M11: f64,
+    pub M12: f64,
+    pub M21: f64,
+    pub M22: f64,
+    pub M31: f64,
+    pub M32: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiDrawBrush {
+    pub Type: uiDrawBrushType,
+    pub R: f64,
+    pub G: f64,
+    pub B: f64,
+    pub A: f64,
+    pub X0: f64,
+    pub Y0: f64,
+    pub X1: f64,
+    pub Y1: f64,
+    pub OuterRadius: f64,
+    pub Stops: *mut uiDrawBrushGradientStop,
+    pub NumStops: usize,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiDrawBrushGradientStop {
+    pub Pos: f64,
+    pub R: f64,
+    pub G: f64,
+    pub B: f64,
+    pub A: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiDrawStrokeParams {
+    pub Cap: uiDrawLineCap,
+    pub Join: uiDrawLineJoin,
+    pub Thickness: f64,
+    pub MiterLimit: f64,
+    pub Dashes: *mut f64,
+    pub NumDashes: usize,
+    pub DashPhase: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiAreaMouseEvent {
+    pub X: f64,
+    pub Y: f64,
+    pub AreaWidth: f64,
+    pub AreaHeight: f64,
+    pub Down: c_int,
+    pub Up: c_int,
+    pub Count: c_int,
+    pub Modifiers: uiModifiers,
+    pub Held1To64: u64,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiAreaKeyEvent {
+    pub Key: c_char,
+    pub ExtKey: uiExtKey,
+    pub Modifier: uiModifiers,
+    pub Modifiers: uiModifiers,
+    pub Up: c_int,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiFontDescriptor {
+    pub Family: *mut c_char,
+    pub Size: f64,
+    pub Weight: uiTextWeight,
+    pub Italic: uiTextItalic,
+    pub Stretch: uiTextStretch,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiTableModelHandler {
+    pub NumColumns: Option<
+        unsafe extern "C" fn(arg1: *mut uiTableModelHandler, arg2: *mut uiTableModel) -> c_int,
+    >,
+    pub ColumnType: Option<
+        unsafe extern "C" fn(
+            arg1: *mut uiTableModelHandler,
+            arg2: *mut uiTableModel,
+            arg3: c_int,
+        ) -> uiTableValueType,
+    >,
+    pub NumRows: Option<
+        unsafe extern "C" fn(arg1: *mut uiTableModelHandler, arg2: *mut uiTableModel) -> c_int,
+    >,
+    pub CellValue: Option<
+        unsafe extern "C" fn(
+            mh: *mut uiTableModelHandler,
+            m: *mut uiTableModel,
+            row: c_int,
+            column: c_int,
+        ) -> *mut uiTableValue,
+    >,
+    pub SetCellValue: Option<
+        unsafe extern "C" fn(
+            arg1: *mut uiTableModelHandler,
+            arg2: *mut uiTableModel,
+            arg3: c_int,
+            arg4: c_int,
+            arg5: *const uiTableValue,
+        ),
+    >,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiTableTextColumnOptionalParams {
+    pub ColorModelColumn: c_int,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiTableParams {
+    pub Model: *mut uiTableModel,
+    pub RowBackgroundColorModelColumn: c_int,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct uiTableSelection {
+    pub NumRows: c_int,
+    pub Rows: *mut c_int,
+}
+
+/// The callback data passed to libui along with a callback.
+pub(crate) type Data = *mut c_void;
@@ -0,0 +1,141 @@
+//! Windows, which are controls with a few more properties.
+
+use mock::controls::{self, create, flag, set_callback, set_flag, with};
+use mock::types::*;
+use std::cell::Cell;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+thread_local! {
+    static FOCUSED: Cell<*mut uiWindow> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// Focuses the window, as happens when it is shown.
+pub(crate) fn focus(w: *mut uiWindow) {
+    let previous = FOCUSED.with(|focused| focused.replace(w));
+    if previous != w {
+        if controls::is_alive(previous) {
+            unsafe { ::mock::fire(previous as *mut uiControl, "focus_changed") };
+        }
+        unsafe { ::mock::fire(w as *mut uiControl, "focus_changed") };
+    }
+}
+
+pub unsafe extern "C" fn uiWindowTitle(w: *mut uiWindow) -> *mut c_char {
+    controls::text(w)
+}
+
+pub unsafe extern "C" fn uiWindowSetTitle(w: *mut uiWindow, title: *const c_char) {
+    controls::set_text(w, title)
+}
+
+pub unsafe extern "C" fn uiWindowPosition(w: *mut uiWindow, x: *mut c_int, y: *mut c_int) {
+    with(w, |node| {
+        *x = node.position.0;
+        *y = node.position.1;
+    })
+}
+
+pub unsafe extern "C" fn uiWindowSetPosition(w: *mut uiWindow, x: c_int, y: c_int) {
+    with(w, |node| node.position = (x, y))
+}
+
+pub unsafe extern "C" fn uiWindowOnPositionChanged(
+    w: *mut uiWindow,
+    f: Option<unsafe extern "C" fn(w: *mut uiWindow, data: Data)>,
+    data: Data,
+) {
+    set_callback(w, "position_changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiWindowContentSize(
+    w: *mut uiWindow,
+    width: *mut c_int,
+    height: *mut c_int,
+) {
+    with(w, |node| {
+        *width = node.size.0;
+        *height = node.size.1;
+    })
+}
+
+pub unsafe extern "C" fn uiWindowSetContentSize(w: *mut uiWindow, width: c_int, height: c_int) {
+    with(w, |node| node.size = (width, height))
+}
+
+pub unsafe extern "C" fn uiWindowFullscreen(w: *mut uiWindow) -> c_int {
+    flag(w, "fullscreen")
+}
+
+pub unsafe extern "C" fn uiWindowSetFullscreen(w: *mut uiWindow, fullscreen: c_int) {
+    set_flag(w, "fullscreen", fullscreen)
+}
+
+pub unsafe extern "C" fn uiWindowOnContentSizeChanged(
+    w: *mut uiWindow,
+    f: Option<unsafe extern "C" fn(w: *mut uiWindow, data: Data)>,
+    data: Data,
+) {
+    set_callback(w, "content_size_changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiWindowOnClosing(
+    w: *mut uiWindow,
+    f: Option<unsafe extern "C" fn(w: *mut uiWindow, data: Data) -> c_int>,
+    data: Data,
+) {
+    set_callback(w, "closing", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiWindowOnFocusChanged(
+    w: *mut uiWindow,
+    f: Option<unsafe extern "C" fn(w: *mut uiWindow, data: Data)>,
+    data: Data,
+) {
+    set_callback(w, "focus_changed", f.map(|f| f as *const ()), data)
+}
+
+pub unsafe extern "C" fn uiWindowFocused(w: *mut uiWindow) -> c_int {
+    with(w, |_| FOCUSED.with(|focused| focused.get() == w) as c_int)
+}
+
+pub unsafe extern "C" fn uiWindowBorderless(w: *mut uiWindow) -> c_int {
+    flag(w, "borderless")
+}
+
+pub unsafe extern "C" fn uiWindowSetBorderless(w: *mut uiWindow, borderless: c_int) {
+    set_flag(w, "borderless", borderless)
+}
+
+pub unsafe extern "C" fn uiWindowSetChild(w: *mut uiWindow, child: *mut uiControl) {
+    controls::set_only_child(w, child)
+}
+
+pub unsafe extern "C" fn uiWindowMargined(w: *mut uiWindow) -> c_int {
+    flag(w, "margined")
+}
+
+pub unsafe extern "C" fn uiWindowSetMargined(w: *mut uiWindow, margined: c_int) {
+    set_flag(w, "margined", margined)
+}
+
+pub unsafe extern "C" fn uiWindowResizeable(w: *mut uiWindow) -> c_int {
+    flag(w, "resizeable")
+}
+
+pub unsafe extern "C" fn uiWindowSetResizeable(w: *mut uiWindow, resizeable: c_int) {
+    set_flag(w, "resizeable", resizeable)
+}
+
+pub unsafe extern "C" fn uiNewWindow(
+    title: *const c_char,
+    width: c_int,
+    height: c_int,
+    has_menubar: c_int,
+) -> *mut uiWindow {
+    let window = create("uiWindow", title);
+    with(window, |node| node.size = (width, height));
+    set_flag(window, "resizeable", 1);
+    set_flag(window, "menubar", has_menubar);
+    window
+}
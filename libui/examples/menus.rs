@@ -110,12 +110,7 @@ fn main() {
 
     // The special MenuItem from `Menu::append_quit_item()` or a `libui::menu! { QuitMenuItem() }` macro
     // doesn't accept a callback with MenuItem::on_clicked(). Instead, call UI::on_should_quit() instead.
-    ui.on_should_quit({
-        let ui = ui.clone();
-        move || {
-            ui.quit();
-        }
-    });
+    ui.on_should_quit(|| QuitAction::Quit);
 
     window.show();
     ui.main();
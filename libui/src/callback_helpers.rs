@@ -1,5 +1,30 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
 use std::mem;
 use std::os::raw::c_void;
+use std::rc::Rc;
+use panics::catch_panic;
+use trace;
+
+/// Identifies a callback by the address of its owner and the event name.
+type CallbackKey = (usize, &'static str);
+
+/// A callback registered with libui, along with the trampoline libui calls it through.
+struct Registered {
+    trampoline: *const (),
+    callback: Box<dyn Any>,
+}
+
+thread_local! {
+    // Callbacks registered with libui.
+    static CALLBACKS: RefCell<BTreeMap<CallbackKey, Registered>> =
+        const { RefCell::new(BTreeMap::new()) };
+    // How many callbacks are currently running.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    // Callbacks which were removed while a callback was running, waiting to be dropped.
+    static GRAVEYARD: RefCell<Vec<Box<dyn Any>>> = const { RefCell::new(Vec::new()) };
+}
 
 /// Transmutes a raw mutable pointer into a mutable reference.
 pub unsafe fn from_void_ptr<'ptr, F>(ptr: *mut c_void) -> &'ptr mut F {
@@ -12,9 +37,142 @@ pub fn to_heap_ptr<F>(item: F) -> *mut c_void {
     Box::into_raw(Box::new(item)) as *mut c_void
 }
 
+/// Registers `callback` as the handler of `event` on the libui object at `owner`, returning
+/// a pointer to it to be passed to libui as the callback data. `trampoline` is the function
+/// libui calls the callback through.
+///
+/// Any callback previously registered for the same event on the same owner is dropped.
+pub fn register_callback<T, F: 'static>(
+    owner: *mut T,
+    event: &'static str,
+    trampoline: *const (),
+    callback: F,
+) -> *mut c_void {
+    let callback = Box::new(callback);
+    let ptr = &*callback as *const F as *mut c_void;
+    let registered = Registered {
+        trampoline,
+        callback,
+    };
+    let previous = CALLBACKS.with(|callbacks| {
+        callbacks
+            .borrow_mut()
+            .insert((owner as usize, event), registered)
+    });
+    if let Some(previous) = previous {
+        dispose(vec![previous.callback]);
+    }
+    ptr
+}
+
+/// Returns the trampoline and the callback data of the callback registered for `event` on
+/// the libui object at `owner`, allowing it to be called as if by libui.
+pub fn registered_callback<T>(
+    owner: *mut T,
+    event: &'static str,
+) -> Option<(*const (), *mut c_void)> {
+    CALLBACKS.with(|callbacks| {
+        callbacks
+            .borrow()
+            .get(&(owner as usize, event))
+            .map(|registered| {
+                let data = &*registered.callback as *const dyn Any as *const () as *mut c_void;
+                (registered.trampoline, data)
+            })
+    })
+}
+
+/// Drops all callbacks registered on the libui object at `owner`.
+pub fn unregister_callbacks<T>(owner: *mut T) {
+    let owner = owner as usize;
+    let removed = CALLBACKS.with(|callbacks| {
+        let mut callbacks = callbacks.borrow_mut();
+        let keys: Vec<_> = callbacks.keys().filter(|key| key.0 == owner).cloned().collect();
+        keys.into_iter()
+            .filter_map(|key| callbacks.remove(&key))
+            .map(|registered| registered.callback)
+            .collect::<Vec<_>>()
+    });
+    dispose(removed);
+}
+
+/// Drops all registered callbacks.
+pub fn clear_callbacks() {
+    let removed = CALLBACKS.with(|callbacks| mem::take(&mut *callbacks.borrow_mut()));
+    dispose(removed.into_values().map(|registered| registered.callback).collect());
+}
+
+/// Runs `f`, which calls into the callback registered for `event` on a control of type `kind`.
+/// Returns `None` if the callback panicked.
+///
+/// Callbacks removed while `f` runs are only dropped once it returns, so a callback may safely
+/// replace itself or destroy its own control.
+pub fn invoke<R, F: FnOnce() -> R>(kind: &'static str, event: &'static str, f: F) -> Option<R> {
+    struct DepthGuard;
+
+    impl Drop for DepthGuard {
+        fn drop(&mut self) {
+            let depth = DEPTH.with(|depth| {
+                depth.set(depth.get() - 1);
+                depth.get()
+            });
+            if depth == 0 {
+                let dead = GRAVEYARD.with(|graveyard| mem::take(&mut *graveyard.borrow_mut()));
+                dispose(dead);
+            }
+        }
+    }
+
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let _guard = DepthGuard;
+    trace::callback(kind, event, || catch_panic(f))
+}
+
+/// A user callback shared between the handles of a compound control.
+pub type SharedCallback<T> = Rc<RefCell<Option<Box<dyn FnMut(T)>>>>;
+
+/// Frees a user callback once the callback registered on the underlying control is dropped, as
+/// the user callback may hold a handle to the control itself.
+pub struct CallbackSlot<T>(pub SharedCallback<T>);
+
+impl<T> CallbackSlot<T> {
+    /// Runs the callback, if any. It is taken out while it runs, so it may replace itself.
+    pub fn call(&self, value: T) {
+        let callback = self.0.borrow_mut().take();
+        if let Some(mut callback) = callback {
+            callback(value);
+            let mut current = self.0.borrow_mut();
+            if current.is_none() {
+                *current = Some(callback);
+            }
+        }
+    }
+}
+
+impl<T> Drop for CallbackSlot<T> {
+    fn drop(&mut self) {
+        let callback = self.0.borrow_mut().take();
+        drop(callback);
+    }
+}
+
+/// Drops the given callbacks, or defers that until no callback is running anymore.
+fn dispose(callbacks: Vec<Box<dyn Any>>) {
+    if callbacks.is_empty() {
+        return;
+    }
+    if DEPTH.with(|depth| depth.get()) > 0 {
+        GRAVEYARD.with(|graveyard| graveyard.borrow_mut().extend(callbacks));
+    } else {
+        // Dropped outside of any borrow, as a callback's destructor might register callbacks.
+        drop(callbacks);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ptr;
     #[test]
     fn ptr_roundtripping() {
         let value: i32 = 1024;
@@ -30,4 +188,47 @@ mod tests {
         assert_eq!(*boxed, expected);
         mem::forget(actual);
     }
+
+    #[test]
+    fn replaced_callbacks_are_dropped() {
+        use std::rc::Rc;
+
+        let owner = 0x10 as *mut c_void;
+        let first = Rc::new(());
+        let second = Rc::new(());
+
+        let captured = first.clone();
+        register_callback(owner, "changed", ptr::null(), move || {
+            assert!(Rc::strong_count(&captured) > 1)
+        });
+        assert_eq!(Rc::strong_count(&first), 2);
+
+        let captured = second.clone();
+        register_callback(owner, "changed", ptr::null(), move || {
+            assert!(Rc::strong_count(&captured) > 1)
+        });
+        assert_eq!(Rc::strong_count(&first), 1);
+        assert_eq!(Rc::strong_count(&second), 2);
+
+        unregister_callbacks(owner);
+        assert_eq!(Rc::strong_count(&second), 1);
+    }
+
+    #[test]
+    fn callbacks_replaced_while_running_are_dropped_afterwards() {
+        use std::rc::Rc;
+
+        let owner = 0x20 as *mut c_void;
+        let token = Rc::new(());
+
+        let captured = token.clone();
+        register_callback(owner, "clicked", ptr::null(), move || {
+            assert!(Rc::strong_count(&captured) > 1)
+        });
+        invoke("Button", "clicked", || {
+            unregister_callbacks(owner);
+            assert_eq!(Rc::strong_count(&token), 2);
+        });
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
 }
@@ -24,6 +24,7 @@
 //!     for i in &v {
 //!         println!("{}", i);
 //!     }
+//!     libui::QuitAction::Quit
 //! });
 //!
 //! ev.quit();
@@ -2,6 +2,9 @@
 
 use controls::Control;
 use draw;
+use ownership;
+use trace;
+use panics::catch_panic;
 use std::mem;
 use std::os::raw::c_int;
 pub use libui_ffi::uiExtKey as ExtKey;
@@ -25,6 +28,11 @@ struct RustAreaHandler {
     trait_object: Box<dyn AreaHandler>,
 }
 
+/// Calls into the `AreaHandler` to handle `event`, catching panics.
+fn handler_callback<R, F: FnOnce() -> R>(event: &'static str, f: F) -> Option<R> {
+    trace::callback("Area", event, || catch_panic(f))
+}
+
 impl RustAreaHandler {
     fn new(trait_object: Box<dyn AreaHandler>) -> Box<RustAreaHandler> {
         return Box::new(RustAreaHandler {
@@ -47,11 +55,12 @@ impl RustAreaHandler {
                 let area = Area::from_ui_area(ui_area);
                 let area_draw_params =
                     AreaDrawParams::from_ui_area_draw_params(&*ui_area_draw_params);
-                (*(ui_area_handler as *mut RustAreaHandler))
-                    .trait_object
-                    .draw(&area, &area_draw_params);
+                handler_callback("draw", || {
+                    (*(ui_area_handler as *mut RustAreaHandler))
+                        .trait_object
+                        .draw(&area, &area_draw_params)
+                });
                 mem::forget(area_draw_params);
-                mem::forget(area);
             }
         }
 
@@ -64,11 +73,12 @@ impl RustAreaHandler {
                 let area = Area::from_ui_area(ui_area);
                 let area_mouse_event =
                     AreaMouseEvent::from_ui_area_mouse_event(&*ui_area_mouse_event);
-                (*(ui_area_handler as *mut RustAreaHandler))
-                    .trait_object
-                    .mouse_event(&area, &area_mouse_event);
+                handler_callback("mouse_event", || {
+                    (*(ui_area_handler as *mut RustAreaHandler))
+                        .trait_object
+                        .mouse_event(&area, &area_mouse_event)
+                });
                 mem::forget(area_mouse_event);
-                mem::forget(area);
             }
         }
 
@@ -79,20 +89,22 @@ impl RustAreaHandler {
         ) {
             unsafe {
                 let area = Area::from_ui_area(ui_area);
-                (*(ui_area_handler as *mut RustAreaHandler))
-                    .trait_object
-                    .mouse_crossed(&area, left != 0);
-                mem::forget(area);
+                handler_callback("mouse_crossed", || {
+                    (*(ui_area_handler as *mut RustAreaHandler))
+                        .trait_object
+                        .mouse_crossed(&area, left != 0)
+                });
             }
         }
 
         extern "C" fn drag_broken(ui_area_handler: *mut uiAreaHandler, ui_area: *mut uiArea) {
             unsafe {
                 let area = Area::from_ui_area(ui_area);
-                (*(ui_area_handler as *mut RustAreaHandler))
-                    .trait_object
-                    .drag_broken(&area);
-                mem::forget(area);
+                handler_callback("drag_broken", || {
+                    (*(ui_area_handler as *mut RustAreaHandler))
+                        .trait_object
+                        .drag_broken(&area)
+                });
             }
         }
 
@@ -104,12 +116,13 @@ impl RustAreaHandler {
             unsafe {
                 let area = Area::from_ui_area(ui_area);
                 let area_key_event = AreaKeyEvent::from_ui_area_key_event(&*ui_area_key_event);
-                let result = (*(ui_area_handler as *mut RustAreaHandler))
-                    .trait_object
-                    .key_event(&area, &area_key_event);
+                let result = handler_callback("key_event", || {
+                    (*(ui_area_handler as *mut RustAreaHandler))
+                        .trait_object
+                        .key_event(&area, &area_key_event)
+                });
                 mem::forget(area_key_event);
-                mem::forget(area);
-                result as c_int
+                result.unwrap_or(false) as c_int
             }
         }
     }
@@ -153,7 +166,7 @@ impl Area {
             let area = Area::from_raw(libui_ffi::uiNewArea(
                 &mut *rust_area_handler as *mut RustAreaHandler as *mut uiAreaHandler,
             ));
-            mem::forget(rust_area_handler);
+            ownership::keep_alive(area.ptr() as *mut uiControl, rust_area_handler);
             area
         }
     }
@@ -167,13 +180,13 @@ impl Area {
                 width as i32,
                 height as i32,
             ));
-            mem::forget(rust_area_handler);
+            ownership::keep_alive(area.ptr() as *mut uiControl, rust_area_handler);
             area
         }
     }
 
     pub unsafe fn from_ui_area(ui_area: *mut uiArea) -> Area {
-        Area { uiArea: ui_area }
+        Area::from_raw(ui_area)
     }
 
     /// Sets the size of the area in points.
@@ -1,5 +1,5 @@
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use std::ffi::{CStr, CString};
 use std::mem;
 use std::os::raw::c_void;
@@ -51,13 +51,22 @@ impl Button {
         where
             G: FnMut(&mut Button),
         {
-            let mut button = Button { uiButton: button };
+            let mut button = unsafe { Button::from_raw(button) };
             unsafe {
-                from_void_ptr::<G>(data)(&mut button);
+                invoke("Button", "clicked", || from_void_ptr::<G>(data)(&mut button));
             }
         }
         unsafe {
-            libui_ffi::uiButtonOnClicked(self.uiButton, Some(c_callback::<F>), to_heap_ptr(callback));
+            libui_ffi::uiButtonOnClicked(
+                self.uiButton,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiButton,
+                    "clicked",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
         }
     }
 }
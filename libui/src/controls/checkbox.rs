@@ -1,5 +1,5 @@
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use std::i32;
 use std::mem;
 use std::os::raw::c_void;
@@ -37,14 +37,19 @@ impl Checkbox {
             G: FnMut(bool),
         {
             let val = unsafe { libui_ffi::uiCheckboxChecked(checkbox) } != 0;
-            unsafe { from_void_ptr::<G>(data)(val) }
+            unsafe { invoke("Checkbox", "toggled", || from_void_ptr::<G>(data)(val)); }
         }
 
         unsafe {
             libui_ffi::uiCheckboxOnToggled(
                 self.uiCheckbox,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiCheckbox,
+                    "toggled",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -1,5 +1,5 @@
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use std::mem;
 use std::os::raw::c_void;
 use ui::UI;
@@ -45,18 +45,21 @@ impl ColorButton {
         where
             G: FnMut(&mut ColorButton),
         {
-            let mut button = ColorButton {
-                uiColorButton: button,
-            };
+            let mut button = unsafe { ColorButton::from_raw(button) };
             unsafe {
-                from_void_ptr::<G>(data)(&mut button);
+                invoke("ColorButton", "changed", || from_void_ptr::<G>(data)(&mut button));
             }
         }
         unsafe {
             libui_ffi::uiColorButtonOnChanged(
                 self.uiColorButton,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiColorButton,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
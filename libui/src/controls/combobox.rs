@@ -1,5 +1,5 @@
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use std::ffi::CStr;
 use std::i32;
 use std::mem;
@@ -65,6 +65,14 @@ impl Combobox {
     }
 
     pub fn on_selected<'ctx, F>(&mut self, _ctx: &'ctx UI, callback: F)
+    where
+        F: FnMut(i32) + 'static,
+    {
+        self.set_on_selected(callback)
+    }
+
+    /// Sets the `on_selected` callback, for use within the crate where no `UI` is at hand.
+    pub(crate) fn set_on_selected<F>(&mut self, callback: F)
     where
         F: FnMut(i32) + 'static,
     {
@@ -73,14 +81,19 @@ impl Combobox {
             G: FnMut(i32),
         {
             let val = unsafe { libui_ffi::uiComboboxSelected(combobox) };
-            unsafe { from_void_ptr::<G>(data)(val) }
+            unsafe { invoke("Combobox", "selected", || from_void_ptr::<G>(data)(val)); }
         }
 
         unsafe {
             libui_ffi::uiComboboxOnSelected(
                 self.uiCombobox,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiCombobox,
+                    "selected",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -142,14 +155,19 @@ impl EditableCombobox {
             unsafe {
                 libui_ffi::uiFreeText(ptr);
             }
-            unsafe { from_void_ptr::<G>(data)(text) }
+            unsafe { invoke("EditableCombobox", "changed", || from_void_ptr::<G>(data)(text)); }
         }
 
         unsafe {
             libui_ffi::uiEditableComboboxOnChanged(
                 self.uiEditableCombobox,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiEditableCombobox,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -1,4 +1,8 @@
 /// Defines a new control, creating a Rust wrapper, a `Deref` implementation, and a destructor.
+///
+/// Each handle to the control is counted; a control without a parent is destroyed when its
+/// last handle is dropped. In debug builds, the generated methods assert that they are called on
+/// the GUI thread.
 /// An example of use:
 /// ```ignore
 ///     define_control!{
@@ -16,54 +20,74 @@ macro_rules! define_control {
         $(#[$attr])*
         pub struct $rust_type {
             $sys_type: *mut $sys_type,
+            generation: $crate::ownership::Generation,
         }
 
         impl Drop for $rust_type {
             fn drop(&mut self) {
-                // For now this does nothing, but in the future, when `libui` supports proper
-                // memory management, this will likely need to twiddle reference counts.
+                if !::std::thread::panicking() {
+                    $crate::ffi_tools::debug_assert_gui_thread(concat!(stringify!($rust_type), "::drop"));
+                }
+                $crate::ownership::release(self.$sys_type as *mut uiControl, self.generation);
             }
         }
 
         impl Clone for $rust_type {
             fn clone(&self) -> $rust_type {
+                $crate::ffi_tools::debug_assert_gui_thread(concat!(stringify!($rust_type), "::clone"));
+                $crate::ownership::retain_handle(self.$sys_type as *mut uiControl, self.generation);
                 $rust_type {
                     $sys_type: self.$sys_type,
+                    generation: self.generation,
                 }
             }
         }
 
         impl Into<Control> for $rust_type {
             fn into(self) -> Control {
-                unsafe {
-                    let control = Control::from_ui_control(self.$sys_type as *mut uiControl);
-                    mem::forget(self);
-                    control
-                }
+                // The handle is moved into the `Control`.
+                let control = Control {
+                    ui_control: self.$sys_type as *mut uiControl,
+                    generation: self.generation,
+                };
+                mem::forget(self);
+                control
+            }
+        }
+
+        impl $crate::controls::ControlType for $rust_type {
+            const KIND: &'static str = stringify!($rust_type);
+
+            unsafe fn from_ui_control_unchecked(ui_control: *mut uiControl) -> $rust_type {
+                $rust_type::from_raw(ui_control as *mut $sys_type)
             }
         }
 
         impl $rust_type {
             // Show this control to the user. This will also show its non-hidden children.
             pub fn show(&mut self) {
+                $crate::ffi_tools::debug_assert_gui_thread(concat!(stringify!($rust_type), "::show"));
                 let control: Control = self.clone().into();
                 unsafe { libui_ffi::uiControlShow(control.ui_control) }
             }
 
             // Hide this control from the user. This will hide its children.
             pub fn hide(&mut self) {
+                $crate::ffi_tools::debug_assert_gui_thread(concat!(stringify!($rust_type), "::hide"));
                 let control: Control = self.clone().into();
                 unsafe { libui_ffi::uiControlHide(control.ui_control) }
             }
 
             // Enable this control.
             pub fn enable(&mut self) {
+                $crate::ffi_tools::debug_assert_gui_thread(concat!(stringify!($rust_type), "::enable"));
                 let control: Control = self.clone().into();
                 unsafe { libui_ffi::uiControlEnable(control.ui_control) }
             }
 
             // Disable this control.
             pub fn disable(&mut self) {
+                $crate::ffi_tools::debug_assert_gui_thread(concat!(stringify!($rust_type), "::disable"));
                 let control: Control = self.clone().into();
                 unsafe { libui_ffi::uiControlDisable(control.ui_control) }
             }
@@ -72,11 +96,17 @@ macro_rules! define_control {
             ///
             /// # Unsafety
             /// The given pointer must point to a valid control or memory unsafety may result.
+            /// The control is destroyed once the last handle to it is dropped while it has no
+            /// parent, even if it was not created by this crate.
             #[allow(non_snake_case)]
             #[allow(unused)]
             pub unsafe fn from_raw($sys_type: *mut $sys_type) -> $rust_type {
+                $crate::ffi_tools::debug_assert_gui_thread(concat!(stringify!($rust_type), "::from_raw"));
+                let generation = $crate::ownership::retain($sys_type as *mut uiControl);
+                $crate::ownership::set_kind($sys_type as *mut uiControl, stringify!($rust_type));
                 $rust_type {
-                    $sys_type: $sys_type
+                    $sys_type: $sys_type,
+                    generation,
                 }
             }
 
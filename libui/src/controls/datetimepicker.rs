@@ -1,5 +1,5 @@
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use libui_ffi::{self, uiControl, uiDateTimePicker};
 use std::mem;
 use std::os::raw::c_void;
@@ -70,18 +70,21 @@ impl DateTimePicker {
         where
             G: FnMut(&mut DateTimePicker),
         {
-            let mut picker = DateTimePicker {
-                uiDateTimePicker: picker,
-            };
+            let mut picker = unsafe { DateTimePicker::from_raw(picker) };
             unsafe {
-                from_void_ptr::<G>(data)(&mut picker);
+                invoke("DateTimePicker", "changed", || from_void_ptr::<G>(data)(&mut picker));
             }
         }
         unsafe {
             libui_ffi::uiDateTimePickerOnChanged(
                 self.uiDateTimePicker,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiDateTimePicker,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -0,0 +1,258 @@
+//! Modal dialogs returning a value.
+
+use super::{
+    Button, CloseAction, Control, HorizontalBox, LayoutStrategy, VerticalBox, Window, WindowType,
+};
+use executor::JoinHandle;
+use libui_ffi;
+use std::cell::{Cell, RefCell};
+use std::os::raw::c_int;
+use std::rc::{Rc, Weak};
+use ui::EventLoop;
+
+/// Receives the result of a dialog once it finished, or `None` if it was cancelled.
+type OnDone<T> = Box<dyn FnOnce(Option<T>)>;
+
+struct DialogState<T> {
+    parent: Window,
+    window: Window,
+    finished: Cell<bool>,
+    // The result of a dialog which finished before it was run.
+    result: RefCell<Option<T>>,
+    on_done: RefCell<Option<OnDone<T>>>,
+    // Keeps a running dialog alive until it finished, as its callbacks only hold weak handles.
+    running: RefCell<Option<Rc<DialogState<T>>>>,
+}
+
+impl<T> DialogState<T> {
+    fn finish(&self, result: Option<T>) {
+        if self.finished.replace(true) {
+            return;
+        }
+        if self.parent.is_alive() {
+            self.parent.clone().enable();
+        }
+        self.window.clone().destroy();
+        let on_done = self.on_done.borrow_mut().take();
+        match on_done {
+            Some(on_done) => on_done(result),
+            None => *self.result.borrow_mut() = result,
+        }
+        // Dropped last, as this may be the last handle to the state.
+        drop(self.running.borrow_mut().take());
+    }
+}
+
+impl<T> Drop for DialogState<T> {
+    fn drop(&mut self) {
+        // A dialog dropped without being run would otherwise be left as a hidden window.
+        if !self.finished.get() && self.window.is_alive() {
+            self.window.clone().destroy();
+        }
+    }
+}
+
+/// Runs `f` with the state of the dialog, unless all handles to it were dropped.
+fn with_state<T, F: FnOnce(&DialogState<T>)>(state: &Weak<DialogState<T>>, f: F) {
+    if let Some(state) = state.upgrade() {
+        f(&state);
+    }
+}
+
+/// A modal window built from any controls, which returns a value of type `T` once accepted.
+///
+/// The dialog consists of a content area and a row of buttons below it. Buttons added with
+/// [`add_button`](#method.add_button) accept the dialog, while those added with
+/// [`add_cancel_button`](#method.add_cancel_button) and closing the window cancel it.
+/// While the dialog is open, its parent window is disabled. Once it finished, the dialog is
+/// destroyed, so it can only be run once. A dialog whose handles are all dropped before it
+/// was run is destroyed as well.
+///
+/// # Example
+///
+/// ```no_run
+/// use libui::controls::*;
+/// use libui::prelude::*;
+///
+/// let ui = UI::init().unwrap();
+/// let window = Window::new(&ui, "Mail", 640, 480, WindowType::NoMenubar);
+///
+/// let user = Entry::new();
+/// let password = PasswordEntry::new();
+/// let mut form = Form::new();
+/// form.append("User", user.clone(), LayoutStrategy::Compact);
+/// form.append("Password", password.clone(), LayoutStrategy::Compact);
+///
+/// let mut login = Dialog::new(&window, "Log in", 300, 100);
+/// login.set_content(form);
+/// login.add_cancel_button("Cancel");
+/// login.add_button("Log in", move || {
+///     if user.value().is_empty() {
+///         None
+///     } else {
+///         Some((user.value(), password.value()))
+///     }
+/// });
+///
+/// if let Some((user, _password)) = login.run() {
+///     println!("Logged in as {}", user);
+/// }
+/// ```
+pub struct Dialog<T> {
+    content: VerticalBox,
+    buttons: HorizontalBox,
+    state: Rc<DialogState<T>>,
+}
+
+impl<T> Clone for Dialog<T> {
+    fn clone(&self) -> Dialog<T> {
+        Dialog {
+            content: self.content.clone(),
+            buttons: self.buttons.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: 'static> Dialog<T> {
+    /// Creates a hidden dialog with the given title and size, which disables `parent` while it
+    /// is open.
+    pub fn new(parent: &Window, title: &str, width: c_int, height: c_int) -> Dialog<T> {
+        let mut window = Window::create(title, width, height, WindowType::NoMenubar);
+        let mut content = VerticalBox::new();
+        content.set_padded(true);
+        let mut buttons = HorizontalBox::new();
+        buttons.set_padded(true);
+        buttons.append(HorizontalBox::new(), LayoutStrategy::Stretchy);
+
+        let mut layout = VerticalBox::new();
+        layout.set_padded(true);
+        layout.append(content.clone(), LayoutStrategy::Stretchy);
+        layout.append(buttons.clone(), LayoutStrategy::Compact);
+        window.set_child(layout);
+
+        let state = Rc::new(DialogState {
+            parent: parent.clone(),
+            window: window.clone(),
+            finished: Cell::new(false),
+            result: RefCell::new(None),
+            on_done: RefCell::new(None),
+            running: RefCell::new(None),
+        });
+        window.set_on_closing({
+            let state = Rc::downgrade(&state);
+            move |_| {
+                with_state(&state, |state| state.finish(None));
+                CloseAction::Keep
+            }
+        });
+        Dialog {
+            content,
+            buttons,
+            state,
+        }
+    }
+
+    /// Sets the control shown above the buttons, replacing the previous one.
+    pub fn set_content<C: Into<Control>>(&mut self, content: C) {
+        while self.content.num_children() > 0 {
+            let _ = self.content.delete(0);
+        }
+        self.content.append(content, LayoutStrategy::Stretchy);
+    }
+
+    /// Adds a button which accepts the dialog with the value returned by `on_clicked`. If it
+    /// returns `None`, such as when the input is not valid, the dialog stays open.
+    pub fn add_button<F>(&mut self, label: &str, mut on_clicked: F)
+    where
+        F: FnMut() -> Option<T> + 'static,
+    {
+        let mut button = Button::new(label);
+        button.on_clicked({
+            let state = Rc::downgrade(&self.state);
+            move |_| {
+                if let Some(value) = on_clicked() {
+                    with_state(&state, |state| state.finish(Some(value)));
+                }
+            }
+        });
+        self.buttons.append(button, LayoutStrategy::Compact);
+    }
+
+    /// Adds a button which cancels the dialog.
+    pub fn add_cancel_button(&mut self, label: &str) {
+        let mut button = Button::new(label);
+        button.on_clicked({
+            let state = Rc::downgrade(&self.state);
+            move |_| with_state(&state, |state| state.finish(None))
+        });
+        self.buttons.append(button, LayoutStrategy::Compact);
+    }
+
+    /// Returns the window of the dialog, for example to change its size.
+    pub fn window(&self) -> Window {
+        self.state.window.clone()
+    }
+
+    /// Closes the dialog, which returns `value`. Does nothing if it finished already.
+    pub fn accept(&self, value: T) {
+        self.state.finish(Some(value))
+    }
+
+    /// Closes the dialog, which returns `None`. Does nothing if it finished already.
+    pub fn cancel(&self) {
+        self.state.finish(None)
+    }
+
+    /// Returns `true` once the dialog was accepted or cancelled.
+    pub fn is_finished(&self) -> bool {
+        self.state.finished.get()
+    }
+
+    /// Shows the dialog and returns once it is finished, with the value it was accepted with,
+    /// or `None` if it was cancelled.
+    ///
+    /// Events keep being handled while waiting, so this can be called from within callbacks.
+    /// If the application quits first, the dialog is cancelled and the quit request is passed
+    /// on, so the enclosing event loop stops as well.
+    pub fn run(&self) -> Option<T> {
+        self.run_nested()
+    }
+
+    /// Shows the dialog like [`run`](#method.run), but returns right away. The returned handle
+    /// resolves to the value once the dialog is finished.
+    pub fn run_async(&self) -> JoinHandle<Option<T>> {
+        let (handle, completion) = JoinHandle::new();
+        self.start(move |result| completion.complete(result));
+        handle
+    }
+
+    /// Runs the dialog in a nested event loop, for use within the crate where no `UI` is at
+    /// hand.
+    pub(crate) fn run_nested(&self) -> Option<T> {
+        let done = Rc::new(RefCell::new(None));
+        self.start({
+            let done = done.clone();
+            move |result| *done.borrow_mut() = Some(result)
+        });
+        if EventLoop::new().run_until(|| done.borrow().is_some()) {
+            done.borrow_mut().take().flatten()
+        } else {
+            self.cancel();
+            unsafe { libui_ffi::uiQuit() };
+            None
+        }
+    }
+
+    /// Shows the dialog, running `on_done` once it is finished.
+    pub(crate) fn start<F: FnOnce(Option<T>) + 'static>(&self, on_done: F) {
+        if self.is_finished() {
+            on_done(self.state.result.borrow_mut().take());
+            return;
+        }
+        *self.state.on_done.borrow_mut() = Some(Box::new(on_done));
+        *self.state.running.borrow_mut() = Some(self.state.clone());
+        self.state.parent.clone().disable();
+        self.state.window.clone().show();
+    }
+}
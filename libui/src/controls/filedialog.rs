@@ -0,0 +1,260 @@
+//! Configurable dialogs to pick files and folders.
+
+use super::Window;
+use libui_ffi::{self, uiFreeText};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+
+/// What a [`FileDialog`](struct.FileDialog.html) lets the user pick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FileDialogKind {
+    Open,
+    Save,
+    Folder,
+}
+
+/// A named set of file name patterns, such as `Images` with `*.png` and `*.jpg`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileFilter {
+    name: String,
+    patterns: Vec<String>,
+}
+
+impl FileFilter {
+    /// Creates a filter from patterns in which `*` matches any number of characters and `?`
+    /// matches a single character. Patterns are matched against the file name, ignoring ASCII
+    /// case.
+    pub fn new(name: &str, patterns: &[&str]) -> FileFilter {
+        FileFilter {
+            name: name.to_string(),
+            patterns: patterns.iter().map(|pattern| pattern.to_string()).collect(),
+        }
+    }
+
+    /// Returns the name of the filter.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the patterns of the filter.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Returns `true` if the file name of `path` matches any of the patterns.
+    pub fn matches(&self, path: &Path) -> bool {
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_ascii_lowercase(),
+            None => return false,
+        };
+        self.patterns.iter().any(|pattern| {
+            let pattern = pattern.to_ascii_lowercase();
+            wildcard_match(pattern.as_bytes(), name.as_bytes())
+        })
+    }
+
+    /// Returns the extension of the first pattern of the form `*.ext`, if any.
+    fn default_extension(&self) -> Option<&str> {
+        self.patterns.iter().find_map(|pattern| {
+            let extension = pattern.strip_prefix("*.")?;
+            if extension.is_empty() || extension.contains(['*', '?']) {
+                None
+            } else {
+                Some(extension)
+            }
+        })
+    }
+}
+
+/// Matches `name` against `pattern`, in which `*` and `?` are wildcards.
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| wildcard_match(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && wildcard_match(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && wildcard_match(rest, &name[1..]),
+    }
+}
+
+/// A dialog to open files, save a file or pick a folder, set up with a builder.
+///
+/// libui-ng takes no options for its dialogs yet, so the system dialog shows all files and
+/// filters are checked once the user picked a file instead. A file to open which matches none
+/// of the filters is refused with an error message, after which the dialog is shown again. The
+/// extension of the first filter is appended to a file to save without an extension.
+///
+/// A starting directory, a suggested file name and picking more than one file can not be set
+/// up until libui-ng supports them.
+///
+/// # Example
+///
+/// ```no_run
+/// use libui::controls::FileDialog;
+/// use libui::prelude::*;
+///
+/// let ui = UI::init().unwrap();
+/// let window = Window::new(&ui, "Viewer", 640, 480, WindowType::NoMenubar);
+///
+/// let dialog = FileDialog::open().filter("Images", &["*.png", "*.jpg"]);
+/// for image in dialog.show(&window) {
+///     println!("Opening {}", image.display());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FileDialog {
+    kind: FileDialogKind,
+    filters: Vec<FileFilter>,
+}
+
+impl FileDialog {
+    fn new(kind: FileDialogKind) -> FileDialog {
+        FileDialog {
+            kind,
+            filters: Vec::new(),
+        }
+    }
+
+    /// Creates a dialog to select existing files.
+    pub fn open() -> FileDialog {
+        FileDialog::new(FileDialogKind::Open)
+    }
+
+    /// Creates a dialog to select a new or existing file to save to.
+    pub fn save() -> FileDialog {
+        FileDialog::new(FileDialogKind::Save)
+    }
+
+    /// Creates a dialog to select an existing folder. Filters do not apply to folders.
+    pub fn folder() -> FileDialog {
+        FileDialog::new(FileDialogKind::Folder)
+    }
+
+    /// Adds a named filter, such as `Images` with the patterns `*.png` and `*.jpg`. If there
+    /// are filters, only files matching one of them can be opened.
+    ///
+    /// libui-ng does not support filters yet, so the dialog shows all files. Picked files are
+    /// checked afterwards instead, see [`show`](#method.show).
+    pub fn filter(mut self, name: &str, patterns: &[&str]) -> FileDialog {
+        self.filters.push(FileFilter::new(name, patterns));
+        self
+    }
+
+    /// Returns the filters added to the dialog.
+    pub fn filters(&self) -> &[FileFilter] {
+        &self.filters
+    }
+
+    /// Shows the dialog as a modal of the window and returns the paths the user picked, which
+    /// is empty if the dialog was cancelled.
+    ///
+    /// A file to open which matches none of the filters is refused, and the dialog is shown
+    /// again. The extension of the first filter is appended to a file to save without an
+    /// extension.
+    pub fn show(&self, window: &Window) -> Vec<PathBuf> {
+        loop {
+            let picked = unsafe {
+                take_path(match self.kind {
+                    FileDialogKind::Open => libui_ffi::uiOpenFile(window.ptr()),
+                    FileDialogKind::Save => libui_ffi::uiSaveFile(window.ptr()),
+                    FileDialogKind::Folder => libui_ffi::uiOpenFolder(window.ptr()),
+                })
+            };
+            match picked.map(|path| self.apply_filters(path)) {
+                Some(Ok(path)) => return vec![path],
+                Some(Err(path)) => window.modal_err("Unsupported file", &self.refusal(&path)),
+                None => return Vec::new(),
+            }
+        }
+    }
+
+    /// Checks a path picked by the user against the filters, returning the path to use or
+    /// `Err` with a file to open which matches none of them.
+    fn apply_filters(&self, path: PathBuf) -> Result<PathBuf, PathBuf> {
+        if self.filters.is_empty() || self.filters.iter().any(|filter| filter.matches(&path)) {
+            return Ok(path);
+        }
+        match self.kind {
+            FileDialogKind::Open => Err(path),
+            FileDialogKind::Save if path.extension().is_none() => {
+                match self.filters.iter().find_map(FileFilter::default_extension) {
+                    Some(extension) => Ok(path.with_extension(extension)),
+                    None => Ok(path),
+                }
+            }
+            FileDialogKind::Save | FileDialogKind::Folder => Ok(path),
+        }
+    }
+
+    /// Explains why a file to open was refused.
+    fn refusal(&self, path: &Path) -> String {
+        let filters: Vec<String> = self
+            .filters
+            .iter()
+            .map(|filter| format!("{} ({})", filter.name, filter.patterns.join(", ")))
+            .collect();
+        format!(
+            "{} can not be opened. Pick one of these files instead: {}.",
+            path.display(),
+            filters.join(", ")
+        )
+    }
+}
+
+/// Turns a path returned by libui into a `PathBuf`, freeing it.
+unsafe fn take_path(ptr: *mut c_char) -> Option<PathBuf> {
+    if ptr.is_null() {
+        return None;
+    }
+    let path: String = CStr::from_ptr(ptr).to_string_lossy().into();
+    uiFreeText(ptr);
+    Some(path.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_match_file_names() {
+        let images = FileFilter::new("Images", &["*.png", "*.JPG", "scan??.tif"]);
+        assert!(images.matches(Path::new("/photos/holiday.png")));
+        assert!(images.matches(Path::new("/photos/holiday.jpg")));
+        assert!(images.matches(Path::new("scan01.tif")));
+        assert!(!images.matches(Path::new("scan1.tif")));
+        assert!(!images.matches(Path::new("/photos/png")));
+        assert!(!images.matches(Path::new("/photos.png/notes.txt")));
+    }
+
+    fn ok(path: &str) -> Result<PathBuf, PathBuf> {
+        Ok(PathBuf::from(path))
+    }
+
+    #[test]
+    fn saved_files_get_the_extension_of_the_first_filter() {
+        let dialog = FileDialog::save()
+            .filter("Any", &["*"])
+            .filter("Documents", &["*.md", "*.txt"]);
+        assert_eq!(dialog.apply_filters(PathBuf::from("notes")), ok("notes"));
+
+        let dialog = FileDialog::save().filter("Documents", &["*.md", "*.txt"]);
+        assert_eq!(dialog.apply_filters(PathBuf::from("notes")), ok("notes.md"));
+        assert_eq!(
+            dialog.apply_filters(PathBuf::from("notes.rst")),
+            ok("notes.rst")
+        );
+    }
+
+    #[test]
+    fn opened_files_matching_no_filter_are_refused() {
+        let dialog = FileDialog::open().filter("Documents", &["*.md"]);
+        assert_eq!(
+            dialog.apply_filters(PathBuf::from("notes.md")),
+            ok("notes.md")
+        );
+        assert_eq!(
+            dialog.apply_filters(PathBuf::from("notes.rst")),
+            Err(PathBuf::from("notes.rst"))
+        );
+    }
+}
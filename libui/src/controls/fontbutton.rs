@@ -1,5 +1,5 @@
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use std::ffi::CStr;
 use std::mem::MaybeUninit;
 use std::os::raw::c_void;
@@ -126,18 +126,21 @@ impl FontButton {
         where
             G: FnMut(&mut FontButton),
         {
-            let mut button = FontButton {
-                uiFontButton: button,
-            };
+            let mut button = unsafe { FontButton::from_raw(button) };
             unsafe {
-                from_void_ptr::<G>(data)(&mut button);
+                invoke("FontButton", "changed", || from_void_ptr::<G>(data)(&mut button));
             }
         }
         unsafe {
             libui_ffi::uiFontButtonOnChanged(
                 self.uiFontButton,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiFontButton,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
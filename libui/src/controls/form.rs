@@ -1,6 +1,7 @@
 use super::{Control, LayoutStrategy};
 use std::ffi::CString;
 use std::mem;
+use ownership;
 use std::os::raw::c_int;
 use libui_ffi::{self, uiControl, uiForm};
 
@@ -14,6 +15,10 @@ define_control! {
     sys_type: uiForm
 }
 
+unsafe fn remove_child(form: *mut uiControl, index: c_int) {
+    libui_ffi::uiFormDelete(form as *mut uiForm, index)
+}
+
 impl Form {
     /// Create a new Form
     pub fn new() -> Form {
@@ -38,6 +43,12 @@ impl Form {
                 stretchy as c_int,
             )
         }
+        ownership::adopt(
+            self.uiForm as *mut uiControl,
+            control.ui_control,
+            None,
+            Some(remove_child),
+        );
     }
 
     /// Returns the number of controls contained within the form.
@@ -46,8 +57,11 @@ impl Form {
     }
 
     /// Removes the control at `index` from the form.
+    ///
+    /// The control is destroyed, unless there are handles to it left.
     pub fn delete(&mut self, index: i32) {
         unsafe { libui_ffi::uiFormDelete(self.uiForm, index) }
+        ownership::disown(self.uiForm as *mut uiControl, index as usize);
     }
 
     /// Returns whether or not controls within the form are padded.
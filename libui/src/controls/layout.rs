@@ -1,8 +1,13 @@
 use super::Control;
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use error::UIError;
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
 use std::mem;
-use std::os::raw::c_int;
+use ownership;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+use std::rc::Rc;
 use libui_ffi::{self, uiAlign, uiAt, uiBox, uiControl, uiGrid, uiGroup, uiSeparator, uiTab};
 
 /// Defines the ways in which the children of boxes can be layed out.
@@ -28,18 +33,14 @@ define_control! {
 impl VerticalBox {
     /// Create a new vertical box layout.
     pub fn new() -> VerticalBox {
-        VerticalBox {
-            uiBox: unsafe { libui_ffi::uiNewVerticalBox() },
-        }
+        unsafe { VerticalBox::from_raw(libui_ffi::uiNewVerticalBox()) }
     }
 }
 
 impl HorizontalBox {
     /// Create a new horizontal box layout.
     pub fn new() -> HorizontalBox {
-        HorizontalBox {
-            uiBox: unsafe { libui_ffi::uiNewHorizontalBox() },
-        }
+        unsafe { HorizontalBox::from_raw(libui_ffi::uiNewHorizontalBox()) }
     }
 }
 
@@ -54,6 +55,26 @@ fn append<T: Into<Control>>(b: *mut uiBox, child: T, strategy: LayoutStrategy) {
         //assert!(ctx.parent_of(control.clone()).is_none());
         libui_ffi::uiBoxAppend(b, control.ui_control, stretchy as c_int)
     }
+    ownership::adopt(b as *mut uiControl, control.ui_control, None, Some(remove_box_child));
+}
+
+unsafe fn remove_box_child(b: *mut uiControl, index: c_int) {
+    libui_ffi::uiBoxDelete(b as *mut uiBox, index)
+}
+
+fn delete(b: *mut uiBox, index: i32) -> Result<(), UIError> {
+    let n = unsafe { libui_ffi::uiBoxNumChildren(b) };
+    if index >= 0 && index < n {
+        unsafe { libui_ffi::uiBoxDelete(b, index) };
+        ownership::disown(b as *mut uiControl, index as usize);
+        Ok(())
+    } else {
+        Err(UIError::BoxIndexOutOfBounds { index, n })
+    }
+}
+
+fn num_children(b: *mut uiBox) -> i32 {
+    unsafe { libui_ffi::uiBoxNumChildren(b) }
 }
 
 fn padded(b: *mut uiBox) -> bool {
@@ -70,6 +91,19 @@ impl VerticalBox {
         append(self.uiBox, child, strategy)
     }
 
+    /// Remove the control at the given index from the box.
+    ///
+    /// The control is destroyed, unless there are handles to it left. Returns an error if the
+    /// index was out of bounds.
+    pub fn delete(&mut self, index: i32) -> Result<(), UIError> {
+        delete(self.uiBox, index)
+    }
+
+    /// Get the number of controls in the box.
+    pub fn num_children(&self) -> i32 {
+        num_children(self.uiBox)
+    }
+
     /// Determine whenther the box provides padding around its children.
     pub fn padded(&self) -> bool {
         padded(self.uiBox)
@@ -87,6 +121,19 @@ impl HorizontalBox {
         append(self.uiBox, child, strategy)
     }
 
+    /// Remove the control at the given index from the box.
+    ///
+    /// The control is destroyed, unless there are handles to it left. Returns an error if the
+    /// index was out of bounds.
+    pub fn delete(&mut self, index: i32) -> Result<(), UIError> {
+        delete(self.uiBox, index)
+    }
+
+    /// Get the number of controls in the box.
+    pub fn num_children(&self) -> i32 {
+        num_children(self.uiBox)
+    }
+
     /// Determine whenther the box provides padding around its children.
     pub fn padded(&self) -> bool {
         padded(self.uiBox)
@@ -143,9 +190,17 @@ impl Group {
         }
     }
 
-    // Set the group's child widget.
+    // Set the group's child widget. The previous child is destroyed, unless there are handles to it left.
     pub fn set_child<T: Into<Control>>(&mut self, child: T) {
-        unsafe { libui_ffi::uiGroupSetChild(self.uiGroup, child.into().ui_control) }
+        unsafe fn remove_child(group: *mut uiControl, _index: c_int) {
+            libui_ffi::uiGroupSetChild(group as *mut uiGroup, ptr::null_mut())
+        }
+
+        let child = child.into();
+        let group = self.uiGroup as *mut uiControl;
+        unsafe { libui_ffi::uiGroupSetChild(self.uiGroup, child.ui_control) }
+        ownership::disown_all(group);
+        ownership::adopt(group, child.ui_control, None, Some(remove_child));
     }
 
     // Check whether or not the group draws a margin.
@@ -173,37 +228,47 @@ impl TabGroup {
         unsafe {
             let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
             libui_ffi::uiTabAppend(self.uiTab, c_string.as_ptr(), control.ui_control);
-            libui_ffi::uiTabNumPages(self.uiTab) as i32
         }
+        page_titles(self.uiTab).borrow_mut().push(name.to_string());
+        ownership::adopt(self.uiTab as *mut uiControl, control.ui_control, None, Some(remove_page));
+        unsafe { libui_ffi::uiTabNumPages(self.uiTab) as i32 }
     }
 
     /// Add the given control before the given index in the tab group, as a new tab with a given name.
     ///
     /// Returns the number of tabs in the group after adding the new tab.
     pub fn insert_at<T: Into<Control>>(&mut self, name: &str, before: i32, control: T) -> i32 {
+        let control = control.into();
         unsafe {
             let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
-            libui_ffi::uiTabInsertAt(
-                self.uiTab,
-                c_string.as_ptr(),
-                before,
-                control.into().ui_control,
-            );
-            libui_ffi::uiTabNumPages(self.uiTab) as i32
+            libui_ffi::uiTabInsertAt(self.uiTab, c_string.as_ptr(), before, control.ui_control);
         }
+        {
+            let titles = page_titles(self.uiTab);
+            let mut titles = titles.borrow_mut();
+            let index = (before.max(0) as usize).min(titles.len());
+            titles.insert(index, name.to_string());
+        }
+        ownership::adopt(
+            self.uiTab as *mut uiControl,
+            control.ui_control,
+            Some(before.max(0) as usize),
+            Some(remove_page),
+        );
+        unsafe { libui_ffi::uiTabNumPages(self.uiTab) as i32 }
     }
 
     /// Remove the control at the given index in the tab group.
     ///
     /// Returns the number of tabs in the group after removing the tab, or an error if that index was out of bounds.
     ///
-    /// NOTE: This will leak the deleted control! We have no way of actually getting it
-    /// to decrement its reference count per `libui`'s UI as of today, unless we maintain a
-    /// separate list of children ourselves…
+    /// The removed control is destroyed, unless there are handles to it left.
     pub fn delete(&mut self, index: i32) -> Result<i32, UIError> {
         let n = unsafe { libui_ffi::uiTabNumPages(self.uiTab) as i32 };
-        if index < n {
+        if index >= 0 && index < n {
             unsafe { libui_ffi::uiTabDelete(self.uiTab, index) };
+            remove_title(self.uiTab, index as usize);
+            ownership::disown(self.uiTab as *mut uiControl, index as usize);
             Ok(n)
         } else {
             Err(UIError::TabGroupIndexOutOfBounds { index, n })
@@ -219,6 +284,118 @@ impl TabGroup {
     pub fn set_margined(&mut self, page: i32, margined: bool) {
         unsafe { libui_ffi::uiTabSetMargined(self.uiTab, page, margined as c_int) }
     }
+
+    /// Returns the number of tabs in the group.
+    pub fn num_pages(&self) -> usize {
+        unsafe { libui_ffi::uiTabNumPages(self.uiTab) as usize }
+    }
+
+    /// Returns the name of the tab at the given index, or `None` if that index is out of bounds.
+    pub fn title(&self, page: usize) -> Option<String> {
+        page_titles(self.uiTab).borrow().get(page).cloned()
+    }
+
+    /// Returns the index of the active tab, or `None` if the group has no tabs.
+    pub fn selected(&self) -> Option<usize> {
+        let index = unsafe { libui_ffi::uiTabSelected(self.uiTab) };
+        if index < 0 {
+            None
+        } else {
+            Some(index as usize)
+        }
+    }
+
+    /// Switches to the tab at the given index, or returns an error if that index is out of
+    /// bounds. The `on_selected` callback is not run.
+    pub fn set_selected(&mut self, index: usize) -> Result<(), UIError> {
+        let n = self.num_pages();
+        if index < n {
+            unsafe { libui_ffi::uiTabSetSelected(self.uiTab, index as c_int) };
+            Ok(())
+        } else {
+            Err(UIError::TabGroupIndexOutOfBounds {
+                index: index as i32,
+                n: n as i32,
+            })
+        }
+    }
+
+    /// Sets a callback to be run with the index of the tab the user switched to.
+    ///
+    /// # Example
+    ///
+    /// Building the content of a tab only once it is first shown:
+    ///
+    /// ```no_run
+    /// use libui::controls::*;
+    /// use libui::prelude::*;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let mut tabs = TabGroup::new();
+    /// tabs.append("Overview", Label::new("Overview"));
+    /// let mut details = VerticalBox::new();
+    /// tabs.append("Details", details.clone());
+    ///
+    /// tabs.on_selected(move |page| {
+    ///     if page == 1 && details.num_children() == 0 {
+    ///         details.append(Label::new("Loaded"), LayoutStrategy::Compact);
+    ///     }
+    /// });
+    /// ```
+    pub fn on_selected<F>(&mut self, callback: F)
+    where
+        F: FnMut(usize) + 'static,
+    {
+        extern "C" fn c_callback<G>(tab: *mut uiTab, data: *mut c_void)
+        where
+            G: FnMut(usize),
+        {
+            let index = unsafe { libui_ffi::uiTabSelected(tab) };
+            if index >= 0 {
+                unsafe {
+                    invoke("TabGroup", "selected", || {
+                        from_void_ptr::<G>(data)(index as usize)
+                    });
+                }
+            }
+        }
+
+        unsafe {
+            libui_ffi::uiTabOnSelected(
+                self.uiTab,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiTab,
+                    "selected",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
+        }
+    }
+}
+
+/// Returns the names of the tabs, which libui does not provide.
+fn page_titles(tab: *mut uiTab) -> Rc<RefCell<Vec<String>>> {
+    let control = tab as *mut uiControl;
+    ownership::kept_alive(control).unwrap_or_else(|| {
+        let titles = Rc::new(RefCell::new(Vec::new()));
+        ownership::keep_alive(control, Box::new(titles.clone()));
+        titles
+    })
+}
+
+fn remove_title(tab: *mut uiTab, index: usize) {
+    let titles = page_titles(tab);
+    let mut titles = titles.borrow_mut();
+    if index < titles.len() {
+        titles.remove(index);
+    }
+}
+
+unsafe fn remove_page(tab: *mut uiControl, index: c_int) {
+    libui_ffi::uiTabDelete(tab as *mut uiTab, index);
+    remove_title(tab as *mut uiTab, index as usize);
 }
 
 define_control! {
@@ -357,10 +534,11 @@ impl LayoutGrid {
             GridExpand::Vertical => (0, 1),
             GridExpand::Both => (1, 1),
         };
+        let control = control.into();
         unsafe {
             libui_ffi::uiGridAppend(
                 self.uiGrid,
-                control.into().ui_control,
+                control.ui_control,
                 left,
                 height,
                 xspan,
@@ -371,6 +549,8 @@ impl LayoutGrid {
                 valign.into_ui_align(),
             );
         }
+        // Children can not be removed from grids.
+        ownership::adopt(self.uiGrid as *mut uiControl, control.ui_control, None, None);
     }
 
     /// Inserts a control in to the `LayoutGrid` relative to an existing control.
@@ -392,10 +572,11 @@ impl LayoutGrid {
             GridExpand::Vertical => (0, 1),
             GridExpand::Both => (1, 1),
         };
+        let control = control.into();
         unsafe {
             libui_ffi::uiGridInsertAt(
                 self.uiGrid,
-                control.into().ui_control,
+                control.ui_control,
                 existing.into().ui_control,
                 at.into_ui_at(),
                 xspan,
@@ -406,5 +587,7 @@ impl LayoutGrid {
                 valign.into_ui_align(),
             );
         }
+        // Children can not be removed from grids.
+        ownership::adopt(self.uiGrid as *mut uiControl, control.ui_control, None, None);
     }
 }
@@ -1,10 +1,15 @@
 //! Available user interface controls and related functionality.
 //!
-//! Note that `Control` and all specific control types are references to memory which is owned by the UI library.
+//! Note that `Control` and all specific control types are counted references to controls owned by the
+//! UI library. A control is destroyed along with its parent, or, if it has no parent, when its last
+//! reference is dropped.
 
+use error::UIError;
+use ownership;
 use ui::UI;
-use libui_ffi::{self, uiControl};
+use libui_ffi::{self, uiControl, uiWindow};
 
+use std::hash::{Hash, Hasher};
 use std::ptr;
 
 #[macro_use]
@@ -21,6 +26,10 @@ mod combobox;
 pub use self::combobox::*;
 mod datetimepicker;
 pub use self::datetimepicker::*;
+mod dialog;
+pub use self::dialog::*;
+mod filedialog;
+pub use self::filedialog::*;
 mod fontbutton;
 pub use self::fontbutton::*;
 mod form;
@@ -33,42 +42,125 @@ mod numericentry;
 pub use self::numericentry::*;
 mod progressbar;
 pub use self::progressbar::*;
+mod question;
+pub use self::question::*;
 mod radiobuttons;
 pub use self::radiobuttons::*;
+mod scaledentry;
+pub use self::scaledentry::*;
 mod table;
 pub use self::table::*;
 mod textentry;
 pub use self::textentry::*;
+mod typedchoice;
+pub use self::typedchoice::*;
+mod validatedentry;
+pub use self::validatedentry::*;
 mod window;
 pub use self::window::*;
 
 /// A generic UI control. Any UI control can be turned into this type.
 ///
-/// Note that `Control` and all specific control types are references
-/// whose memory is owned by the UI library.
+/// Note that `Control` and all specific control types are counted references
+/// to controls owned by the UI library.
 pub struct Control {
     ui_control: *mut uiControl,
+    generation: ownership::Generation,
+}
+
+/// Implemented by all specific control types, allowing a `Control` to be turned back into them
+/// with [`Control::downcast`](struct.Control.html#method.downcast).
+pub trait ControlType: Into<Control> {
+    /// The name of the control type, as returned by [`Control::kind`](struct.Control.html#method.kind).
+    const KIND: &'static str;
+
+    #[doc(hidden)]
+    unsafe fn from_ui_control_unchecked(ui_control: *mut uiControl) -> Self;
 }
 
 impl Drop for Control {
     fn drop(&mut self) {
-        // For now this does nothing, but in the future, when `libui` supports proper memory
-        // management, this will likely need to twiddle reference counts.
+        ownership::release(self.ui_control, self.generation);
     }
 }
 
 impl Clone for Control {
     fn clone(&self) -> Control {
+        ownership::retain_handle(self.ui_control, self.generation);
         Control {
             ui_control: self.ui_control,
+            generation: self.generation,
         }
     }
 }
 
+// A stale handle is not equal to a control created later at the same address.
+impl PartialEq for Control {
+    fn eq(&self, other: &Control) -> bool {
+        self.ui_control == other.ui_control && self.generation == other.generation
+    }
+}
+
+impl Eq for Control {}
+
+impl Hash for Control {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ui_control.hash(state);
+        self.generation.hash(state);
+    }
+}
+
 impl Control {
     /// Creates a new `Control` object from an existing `*mut uiControl`.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must point to a valid control, and this must be called on the GUI thread.
+    ///
+    /// The returned handle takes part in owning the control, like any handle created by this
+    /// crate. This includes controls the crate did not create: once the last handle to a
+    /// control without a parent is dropped, the control is destroyed. Only wrap such a control
+    /// if it may be destroyed that way, and do not destroy it yourself while handles remain.
     pub unsafe fn from_ui_control(ui_control: *mut uiControl) -> Control {
-        Control { ui_control }
+        let generation = ownership::retain(ui_control);
+        Control {
+            ui_control,
+            generation,
+        }
+    }
+
+    /// Returns the name of the specific control type this control was created as, such as
+    /// `"Button"`, or `None` if it was not created by this crate or was destroyed.
+    pub fn kind(&self) -> Option<&'static str> {
+        if ownership::is_current(self.ui_control, self.generation) {
+            ownership::kind(self.ui_control)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the specific control this control was created as, or `None` if it is of a
+    /// different type.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libui::prelude::*;
+    /// use libui::controls::{Button, Control, Label};
+    ///
+    /// let _ui = UI::init().unwrap();
+    /// let control: Control = Button::new("Click me").into();
+    ///
+    /// assert_eq!(control.kind(), Some("Button"));
+    /// assert!(control.downcast::<Label>().is_none());
+    /// let _button: Button = control.downcast().unwrap();
+    /// ```
+    pub fn downcast<T: ControlType>(&self) -> Option<T> {
+        if self.kind() == Some(T::KIND) {
+            Some(unsafe { T::from_ui_control_unchecked(self.ui_control) })
+        } else {
+            None
+        }
     }
 
     /// Returns the underlying `*mut uiControl`.
@@ -76,11 +168,21 @@ impl Control {
         self.ui_control
     }
 
-    /// Destroys a control. Any use of the control after this is use-after-free; therefore, this
-    /// is marked unsafe.
-    pub unsafe fn destroy(&self) {
-        // Don't check for initialization here since this can be run during deinitialization.
-        libui_ffi::uiControlDestroy(self.ui_control)
+    /// Destroys a control along with its children.
+    ///
+    /// The control is removed from its parent and hidden, and its callbacks are dropped right
+    /// away. It is freed once the last handle to it is dropped.
+    ///
+    /// Fails with a [`ChildNotRemovableError`](../enum.UIError.html#variant.ChildNotRemovableError)
+    /// if the parent of the control does not support removing children.
+    pub fn destroy(self) -> Result<(), UIError> {
+        if !ownership::remove_from_parent(self.ui_control) {
+            return Err(UIError::ChildNotRemovableError());
+        }
+        Window::unregister(self.ui_control as *mut uiWindow);
+        unsafe { libui_ffi::uiControlHide(self.ui_control) };
+        ownership::unregister_tree_callbacks(self.ui_control);
+        Ok(())
     }
 }
 
@@ -146,3 +248,25 @@ impl UI {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::mem;
+
+    #[test]
+    fn handles_of_different_generations_differ() {
+        let control = |generation| Control {
+            ui_control: 0x10 as *mut uiControl,
+            generation,
+        };
+        let (first, same, later) = (control(1), control(1), control(2));
+        assert!(first == same);
+        assert!(first != later);
+        let set: HashSet<&Control> = [&first, &same, &later].iter().cloned().collect();
+        assert_eq!(set.len(), 2);
+        // Never registered, so they must not be released.
+        mem::forget((first, same, later));
+    }
+}
@@ -2,10 +2,10 @@
 //!
 
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use std::i32;
 use std::mem;
-use std::os::raw::c_void;
+use std::os::raw::{c_int, c_void};
 use libui_ffi::{self, uiControl, uiSlider, uiSpinbox};
 
 pub trait NumericEntry {
@@ -43,6 +43,55 @@ impl Slider {
     pub fn new(min: i32, max: i32) -> Self {
         unsafe { Slider::from_raw(libui_ffi::uiNewSlider(min, max)) }
     }
+
+    /// Changes the range of values the slider can produce. The value is clamped to the new
+    /// range.
+    pub fn set_range(&mut self, min: i32, max: i32) {
+        unsafe { libui_ffi::uiSliderSetRange(self.uiSlider, min, max) }
+    }
+
+    /// Returns `true` if the slider shows a tooltip with its value while it is dragged.
+    pub fn has_tooltip(&self) -> bool {
+        unsafe { libui_ffi::uiSliderHasToolTip(self.uiSlider) != 0 }
+    }
+
+    /// Sets whether the slider shows a tooltip with its value while it is dragged. Enabled by
+    /// default.
+    pub fn set_has_tooltip(&mut self, has_tooltip: bool) {
+        unsafe { libui_ffi::uiSliderSetHasToolTip(self.uiSlider, has_tooltip as c_int) }
+    }
+
+    /// Sets a callback to be run with the final value when the user releases the slider.
+    ///
+    /// Unlike `on_changed`, which runs for every step while dragging, this runs once per drag,
+    /// which suits expensive work.
+    pub fn on_released<F>(&mut self, callback: F)
+    where
+        F: FnMut(i32) + 'static,
+    {
+        extern "C" fn c_callback<G>(slider: *mut uiSlider, data: *mut c_void)
+        where
+            G: FnMut(i32),
+        {
+            let val = unsafe { libui_ffi::uiSliderValue(slider) };
+            unsafe {
+                invoke("Slider", "released", || from_void_ptr::<G>(data)(val));
+            }
+        }
+
+        unsafe {
+            libui_ffi::uiSliderOnReleased(
+                self.uiSlider,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiSlider,
+                    "released",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
+        }
+    }
 }
 
 impl NumericEntry for Spinbox {
@@ -64,7 +113,7 @@ impl NumericEntry for Spinbox {
         {
             let val = unsafe { libui_ffi::uiSpinboxValue(spinbox) };
             unsafe {
-                from_void_ptr::<G>(data)(val);
+                invoke("Spinbox", "changed", || from_void_ptr::<G>(data)(val));
             }
         }
 
@@ -72,7 +121,12 @@ impl NumericEntry for Spinbox {
             libui_ffi::uiSpinboxOnChanged(
                 self.uiSpinbox,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiSpinbox,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -97,12 +151,21 @@ impl NumericEntry for Slider {
         {
             let val = unsafe { libui_ffi::uiSliderValue(slider) };
             unsafe {
-                from_void_ptr::<G>(data)(val);
+                invoke("Slider", "changed", || from_void_ptr::<G>(data)(val));
             }
         }
 
         unsafe {
-            libui_ffi::uiSliderOnChanged(self.uiSlider, Some(c_callback::<F>), to_heap_ptr(callback));
+            libui_ffi::uiSliderOnChanged(
+                self.uiSlider,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiSlider,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
         }
     }
 }
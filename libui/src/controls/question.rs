@@ -0,0 +1,98 @@
+//! Questions asked in a modal window, answered by clicking one of its buttons.
+
+use super::{Dialog, Label, Window};
+use executor::JoinHandle;
+
+/// The answer to a question asked with [`Window::ask`](struct.Window.html#method.ask).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Response {
+    /// The button at the index was clicked.
+    Button(usize),
+    /// The question was closed without clicking a button.
+    Closed,
+}
+
+impl Response {
+    /// Returns the index of the button which was clicked, or `None` if the question was closed.
+    pub fn button(self) -> Option<usize> {
+        match self {
+            Response::Button(index) => Some(index),
+            Response::Closed => None,
+        }
+    }
+}
+
+impl Window {
+    /// Asks a question in a window of its own, with a button for each possible answer, and
+    /// returns once the user answered.
+    ///
+    /// This window is disabled while the question is open. Events keep being handled while
+    /// waiting, so this can be called from within callbacks. If the application quits first,
+    /// the question is closed and the quit request is passed on, so the enclosing event loop
+    /// stops as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libui::controls::Response;
+    /// use libui::prelude::*;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Editor", 640, 480, WindowType::NoMenubar);
+    /// window.on_closing(&ui, |window| {
+    ///     let buttons = ["Save", "Discard", "Cancel"];
+    ///     match window.ask("Unsaved changes", "Save changes before closing?", &buttons) {
+    ///         Response::Button(0) => {
+    ///             // Save the document.
+    ///             CloseAction::Destroy
+    ///         }
+    ///         Response::Button(1) => CloseAction::Destroy,
+    ///         _ => CloseAction::Keep,
+    ///     }
+    /// });
+    /// ```
+    pub fn ask(&self, title: &str, text: &str, buttons: &[&str]) -> Response {
+        self.question(title, text, buttons)
+            .run_nested()
+            .map_or(Response::Closed, Response::Button)
+    }
+
+    /// Asks a question like [`ask`](#method.ask), but returns right away. The returned handle
+    /// resolves to the response once the user answered.
+    ///
+    /// # Example
+    ///
+    /// ```no_run,edition2018
+    /// use libui::controls::Response;
+    /// use libui::prelude::*;
+    ///
+    /// # fn main() {
+    /// let ui = UI::init().unwrap();
+    /// let window = Window::new(&ui, "Editor", 640, 480, WindowType::NoMenubar);
+    /// let response = window.ask_async("Delete", "Delete the file?", &["Delete", "Cancel"]);
+    /// ui.spawn_local(async move {
+    ///     if response.await == Response::Button(0) {
+    ///         // Delete the file.
+    ///     }
+    /// });
+    /// ui.main();
+    /// # }
+    /// ```
+    pub fn ask_async(&self, title: &str, text: &str, buttons: &[&str]) -> JoinHandle<Response> {
+        let (handle, completion) = JoinHandle::new();
+        self.question(title, text, buttons).start(move |index| {
+            completion.complete(index.map_or(Response::Closed, Response::Button))
+        });
+        handle
+    }
+
+    /// Builds a dialog returning the index of the clicked button.
+    fn question(&self, title: &str, text: &str, buttons: &[&str]) -> Dialog<usize> {
+        let mut dialog = Dialog::new(self, title, 320, 120);
+        dialog.set_content(Label::new(text));
+        for (index, &label) in buttons.iter().enumerate() {
+            dialog.add_button(label, move || Some(index));
+        }
+        dialog
+    }
+}
@@ -1,4 +1,5 @@
 use super::Control;
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use std::ffi::CString;
 use std::i32;
 use std::mem;
@@ -35,20 +36,26 @@ impl RadioButtons {
     }
 
     pub fn on_selected<'ctx, F: FnMut(i32) + 'static>(&self, _ctx: &'ctx UI, callback: F) {
+        self.set_on_selected(callback)
+    }
+
+    /// Sets the `on_selected` callback, for use within the crate where no `UI` is at hand.
+    pub(crate) fn set_on_selected<F: FnMut(i32) + 'static>(&self, callback: F) {
         unsafe {
-            let mut data: Box<Box<dyn FnMut(i32)>> = Box::new(Box::new(callback));
+            let data: Box<dyn FnMut(i32)> = Box::new(callback);
             libui_ffi::uiRadioButtonsOnSelected(
                 self.uiRadioButtons,
                 Some(c_callback),
-                &mut *data as *mut Box<dyn FnMut(i32)> as *mut c_void,
+                register_callback(self.uiRadioButtons, "selected", c_callback as *const (), data),
             );
-            mem::forget(data);
         }
 
         extern "C" fn c_callback(radio_buttons: *mut uiRadioButtons, data: *mut c_void) {
             unsafe {
                 let val = libui_ffi::uiRadioButtonsSelected(radio_buttons);
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(i32)>>(data)(val);
+                invoke("RadioButtons", "selected", || {
+                    from_void_ptr::<Box<dyn FnMut(i32)>>(data)(val)
+                });
             }
         }
     }
@@ -0,0 +1,304 @@
+//! Controls stepping through fractional values, built on the integer `Spinbox` and `Slider`.
+
+use super::{Control, HorizontalBox, Label, LayoutStrategy, NumericEntry, Slider, Spinbox};
+use callback_helpers::{CallbackSlot, SharedCallback};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Maps the integers `0..=steps` onto `min`, `min + step`, ... `max`.
+#[derive(Clone, Copy, Debug)]
+struct Scale {
+    min: f64,
+    step: f64,
+    steps: i32,
+    precision: usize,
+}
+
+impl Scale {
+    fn new(min: f64, max: f64, step: f64, precision: usize) -> Scale {
+        assert!(
+            min.is_finite() && max.is_finite() && min <= max,
+            "invalid range {}..={}",
+            min,
+            max
+        );
+        assert!(step.is_finite() && step > 0.0, "invalid step {}", step);
+        // Otherwise consecutive steps could be rounded to the same value.
+        assert!(
+            step * 10f64.powi(precision.min(i32::MAX as usize) as i32) >= 1.0 - 1e-9,
+            "precision {} is too small for steps of {}",
+            precision,
+            step
+        );
+        // Rounded down, so the last step does not go past `max`. The epsilon makes up for steps
+        // such as 0.1, which floating point numbers can not represent exactly.
+        let steps = ((max - min) / step + 1e-9).floor();
+        assert!(
+            steps <= i32::MAX as f64,
+            "too many steps of {} between {} and {}",
+            step,
+            min,
+            max
+        );
+        Scale {
+            min,
+            step,
+            steps: steps as i32,
+            precision,
+        }
+    }
+
+    /// Returns the index of the step closest to `value`, clamped to the range.
+    fn index_of(&self, value: f64) -> i32 {
+        let index = ((value - self.min) / self.step).round();
+        if index.is_nan() {
+            0
+        } else {
+            index.max(0.0).min(self.steps as f64) as i32
+        }
+    }
+
+    /// Returns the value at step `index`, rounded to the precision so that values shown to the
+    /// user are returned exactly.
+    fn value_at(&self, index: i32) -> f64 {
+        let value = self.min + f64::from(index) * self.step;
+        self.format(value).parse().unwrap_or(value)
+    }
+
+    fn format(&self, value: f64) -> String {
+        format!("{:.*}", self.precision, value)
+    }
+}
+
+/// A control stepping through fractional values, such as `0.05` or `12.5`, built on an integer
+/// [`Slider`](struct.Slider.html) or [`Spinbox`](struct.Spinbox.html). The user picks a value
+/// by moving the slider or stepping the spinbox, not by typing it in.
+///
+/// Use it as a [`ScaledSlider`](type.ScaledSlider.html) or a
+/// [`DecimalSpinbox`](type.DecimalSpinbox.html). The range from `min` to `max` is divided into
+/// steps of `step`, each of which is a value of the underlying control. If the range is not a
+/// whole number of steps, the last step is the one just below `max`. Values are rounded to
+/// `precision` decimal places and shown in a label next to the control, so any value shown is
+/// returned exactly by `value()`.
+pub struct ScaledEntry<E> {
+    container: HorizontalBox,
+    entry: E,
+    label: Label,
+    scale: Scale,
+    on_changed: SharedCallback<f64>,
+}
+
+/// A slider for fractional values. See [`ScaledEntry`](struct.ScaledEntry.html).
+///
+/// # Example
+///
+/// ```no_run
+/// use libui::controls::*;
+/// use libui::prelude::*;
+///
+/// let ui = UI::init().unwrap();
+/// let mut volume = ScaledSlider::new(0.0, 1.0, 0.05, 2);
+/// volume.set_value(0.5);
+/// volume.on_changed(|volume| println!("Volume: {}", volume));
+///
+/// let mut layout = VerticalBox::new();
+/// layout.append(volume.clone(), LayoutStrategy::Compact);
+/// ```
+pub type ScaledSlider = ScaledEntry<Slider>;
+
+/// A spinbox stepping through fractional values. See [`ScaledEntry`](struct.ScaledEntry.html).
+///
+/// # Note
+///
+/// libui only has integer spinboxes, so **the number in the spinbox is the index of the step,
+/// not the value**, and the user can not type in a fractional value. For a range from `0.0`
+/// in steps of `0.5`, the spinbox shows `3` for the value `1.5`, which is shown in the label
+/// next to it. If the user is to type in values, use a
+/// [`ValidatedEntry<f64>`](struct.ValidatedEntry.html) instead.
+pub type DecimalSpinbox = ScaledEntry<Spinbox>;
+
+impl<E: Clone> Clone for ScaledEntry<E> {
+    fn clone(&self) -> ScaledEntry<E> {
+        ScaledEntry {
+            container: self.container.clone(),
+            entry: self.entry.clone(),
+            label: self.label.clone(),
+            scale: self.scale,
+            on_changed: self.on_changed.clone(),
+        }
+    }
+}
+
+impl<E> From<ScaledEntry<E>> for Control {
+    fn from(entry: ScaledEntry<E>) -> Control {
+        entry.container.into()
+    }
+}
+
+impl ScaledEntry<Slider> {
+    /// Creates a slider producing values from `min` to `max` in steps of `step`, rounded to
+    /// `precision` decimal places. The value starts at `min`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range or the step is not finite, if `min` is greater than `max`, if `step`
+    /// is not positive, if `precision` has too few decimal places to tell consecutive steps
+    /// apart, or if there are more steps than fit in an `i32`.
+    pub fn new(min: f64, max: f64, step: f64, precision: usize) -> ScaledSlider {
+        let scale = Scale::new(min, max, step, precision);
+        let mut slider = Slider::new(0, scale.steps);
+        // The tooltip would show the index of the step rather than the value.
+        slider.set_has_tooltip(false);
+        ScaledEntry::with_entry(slider, scale)
+    }
+}
+
+impl ScaledEntry<Spinbox> {
+    /// Creates a spinbox producing values from `min` to `max` in steps of `step`, rounded to
+    /// `precision` decimal places. The value starts at `min`.
+    ///
+    /// The spinbox shows the index of the step rather than the value, see
+    /// [`DecimalSpinbox`](type.DecimalSpinbox.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range or the step is not finite, if `min` is greater than `max`, if `step`
+    /// is not positive, if `precision` has too few decimal places to tell consecutive steps
+    /// apart, or if there are more steps than fit in an `i32`.
+    pub fn new(min: f64, max: f64, step: f64, precision: usize) -> DecimalSpinbox {
+        let scale = Scale::new(min, max, step, precision);
+        ScaledEntry::with_entry(Spinbox::new(0, scale.steps), scale)
+    }
+}
+
+impl<E> ScaledEntry<E>
+where
+    E: NumericEntry + Clone + Into<Control> + 'static,
+{
+    fn with_entry(mut entry: E, scale: Scale) -> ScaledEntry<E> {
+        let mut container = HorizontalBox::new();
+        let label = Label::new(&scale.format(scale.value_at(0)));
+        let on_changed: SharedCallback<f64> = Rc::new(RefCell::new(None));
+
+        entry.on_changed({
+            let mut label = label.clone();
+            let slot = CallbackSlot(on_changed.clone());
+            move |index| {
+                let value = scale.value_at(index);
+                label.set_text(&scale.format(value));
+                slot.call(value);
+            }
+        });
+
+        container.append(entry.clone(), LayoutStrategy::Stretchy);
+        container.append(label.clone(), LayoutStrategy::Compact);
+        ScaledEntry {
+            container,
+            entry,
+            label,
+            scale,
+            on_changed,
+        }
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> f64 {
+        self.scale.value_at(self.entry.value())
+    }
+
+    /// Sets the value to the step closest to `value`, clamped to the range. The `on_changed`
+    /// callback is not run.
+    pub fn set_value(&mut self, value: f64) {
+        let index = self.scale.index_of(value);
+        self.entry.set_value(index);
+        self.label
+            .set_text(&self.scale.format(self.scale.value_at(index)));
+    }
+
+    /// Sets a callback to be run with the new value whenever the user changes it.
+    pub fn on_changed<F: FnMut(f64) + 'static>(&mut self, callback: F) {
+        *self.on_changed.borrow_mut() = Some(Box::new(callback));
+    }
+
+    /// Returns the underlying integer control, whose values are the indices of the steps.
+    pub fn entry(&self) -> E {
+        self.entry.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_rounded_to_the_closest_step() {
+        let scale = Scale::new(0.0, 1.0, 0.05, 2);
+        assert_eq!(scale.steps, 20);
+        assert_eq!(scale.value_at(6), 0.3);
+        assert_eq!(scale.index_of(0.71), 14);
+        assert_eq!(scale.value_at(14), 0.7);
+        assert_eq!(scale.index_of(2.0), 20);
+        assert_eq!(scale.index_of(-1.0), 0);
+        assert_eq!(scale.index_of(f64::NAN), 0);
+
+        let scale = Scale::new(-5.0, 20.0, 2.5, 1);
+        for &value in &[-5.0, -2.5, 0.0, 12.5, 20.0] {
+            assert_eq!(scale.value_at(scale.index_of(value)), value);
+        }
+        assert_eq!(scale.value_at(7), 12.5);
+        assert_eq!(scale.format(12.5), "12.5");
+    }
+
+    #[test]
+    fn precision_must_tell_steps_apart() {
+        assert_eq!(Scale::new(0.0, 1.0, 0.05, 2).steps, 20);
+        assert_eq!(Scale::new(0.0, 10.0, 1.0, 0).steps, 10);
+        assert_eq!(Scale::new(0.0, 1.0, 0.1, 1).steps, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "precision 0 is too small for steps of 0.05")]
+    fn precision_too_small_for_the_step_panics() {
+        Scale::new(0.0, 1.0, 0.05, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "precision 1 is too small for steps of 0.05")]
+    fn precision_one_place_too_small_panics() {
+        Scale::new(0.0, 1.0, 0.05, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid step 0")]
+    fn zero_steps_panic() {
+        Scale::new(0.0, 1.0, 0.0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid step -0.1")]
+    fn negative_steps_panic() {
+        Scale::new(0.0, 1.0, -0.1, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid range 1..=0")]
+    fn reversed_ranges_panic() {
+        Scale::new(1.0, 0.0, 0.1, 2);
+    }
+
+    #[test]
+    fn steps_do_not_go_past_the_maximum() {
+        let scale = Scale::new(0.0, 1.0, 0.35, 2);
+        assert_eq!(scale.steps, 2);
+        assert_eq!(scale.value_at(scale.index_of(1.0)), 0.7);
+        assert_eq!(scale.value_at(scale.index_of(0.6)), 0.7);
+
+        let scale = Scale::new(0.0, 1.0, 0.1, 1);
+        assert_eq!(scale.steps, 10);
+        assert_eq!(scale.value_at(scale.index_of(1.0)), 1.0);
+
+        let scale = Scale::new(-5.0, 20.0, 2.5, 1);
+        assert_eq!(scale.steps, 10);
+        assert_eq!(scale.value_at(scale.index_of(20.0)), 20.0);
+    }
+}
@@ -1,5 +1,9 @@
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
+use ffi_tools;
+use ownership;
+use trace;
+use panics::catch_panic;
 use libui_ffi::{
     self, uiControl, uiSortIndicator, uiTable, uiTableModel, uiTableModelHandler, uiTableParams,
     uiTableSelectionMode, uiTableValue, uiTableValueType,
@@ -68,11 +72,16 @@ pub trait TableDataSource {
     fn set_cell(&mut self, column: i32, row: i32, value: TableValue);
 }
 
+/// Calls into the `TableDataSource` to handle `event`, catching panics.
+fn model_callback<R, F: FnOnce() -> R>(event: &'static str, f: F) -> Option<R> {
+    trace::callback("TableModel", event, || catch_panic(f))
+}
+
 extern "C" fn c_num_columns(
     ui_handler: *mut uiTableModelHandler,
     _ui_model: *mut uiTableModel,
 ) -> c_int {
-    unsafe {
+    model_callback("num_columns", || unsafe {
         // This cast is safe because RustTableModelHandler has a compatible layout.
         // Unfortunately we can't do the same with `ui_model` because we don't store
         // the object itself but just a pointer we didn't create.
@@ -81,19 +90,21 @@ extern "C" fn c_num_columns(
             .trait_object
             .borrow_mut()
             .num_columns()
-    }
+    })
+    .unwrap_or(0)
 }
 
 extern "C" fn c_num_rows(
     ui_handler: *mut uiTableModelHandler,
     _ui_model: *mut uiTableModel,
 ) -> c_int {
-    unsafe {
+    model_callback("num_rows", || unsafe {
         (*(ui_handler as *mut RustTableModelHandler))
             .trait_object
             .borrow_mut()
             .num_rows()
-    }
+    })
+    .unwrap_or(0)
 }
 
 extern "C" fn c_column_type(
@@ -101,14 +112,18 @@ extern "C" fn c_column_type(
     _ui_model: *mut uiTableModel,
     column: c_int,
 ) -> uiTableValueType {
-    let t = unsafe {
+    column_type(ui_handler, column)
+        .unwrap_or(TableValueType::String)
+        .into_ui()
+}
+
+fn column_type(ui_handler: *mut uiTableModelHandler, column: c_int) -> Option<TableValueType> {
+    model_callback("column_type", || unsafe {
         (*(ui_handler as *mut RustTableModelHandler))
             .trait_object
             .borrow_mut()
             .column_type(column)
-    };
-
-    t.into_ui()
+    })
 }
 
 extern "C" fn c_cell_value(
@@ -117,12 +132,23 @@ extern "C" fn c_cell_value(
     row: c_int,
     column: c_int,
 ) -> *mut uiTableValue {
-    let value = unsafe {
+    let value = model_callback("cell", || unsafe {
         (*(ui_handler as *mut RustTableModelHandler))
             .trait_object
             .borrow_mut()
             .cell(column, row)
-    };
+    })
+    // libui expects a value of the column's type, even if the model failed to provide one.
+    .unwrap_or_else(|| match column_type(ui_handler, column) {
+        Some(TableValueType::Int) => TableValue::Int(0),
+        Some(TableValueType::Color) => TableValue::Color {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+        },
+        _ => TableValue::String(String::new()),
+    });
 
     match value {
         TableValue::Int(v) => unsafe { libui_ffi::uiNewTableValueInt(v) },
@@ -147,37 +173,39 @@ extern "C" fn c_set_cell_value(
         // this one instance, so we provide an integer instead. The function is only ever
         // called for clicks anyway, making a check on the users side unnecessary.
         if value == std::ptr::null() {
-            (*(ui_handler as *mut RustTableModelHandler))
-                .trait_object
-                .borrow_mut()
-                .set_cell(column, row, TableValue::Int(0));
+            model_callback("set_cell", || {
+                (*(ui_handler as *mut RustTableModelHandler))
+                    .trait_object
+                    .borrow_mut()
+                    .set_cell(column, row, TableValue::Int(0))
+            });
             return;
         }
 
-        let vt = libui_ffi::uiTableValueGetType(value);
-        let rt = TableValueType::from_ui(vt);
-
-        let rust_value = match rt {
-            TableValueType::Int => {
-                let i = libui_ffi::uiTableValueInt(value);
-                TableValue::Int(i)
-            }
-            TableValueType::String => {
-                let s = libui_ffi::uiTableValueString(value);
-                TableValue::String(CStr::from_ptr(s).to_string_lossy().into_owned())
-            }
-            TableValueType::Color => {
-                let (mut r, mut g, mut b, mut a) = (0.0, 0.0, 0.0, 0.0);
-                libui_ffi::uiTableValueColor(value, &mut r, &mut g, &mut b, &mut a);
-                TableValue::Color { r, g, b, a }
-            }
-            _ => panic!("Unsupported table value type"),
-        };
-
-        (*(ui_handler as *mut RustTableModelHandler))
-            .trait_object
-            .borrow_mut()
-            .set_cell(column, row, rust_value);
+        // The conversion runs within the callback as well, so an unsupported value type
+        // panics there instead of unwinding into C.
+        model_callback("set_cell", || {
+            let rust_value = match TableValueType::from_ui(libui_ffi::uiTableValueGetType(value)) {
+                TableValueType::Int => {
+                    let i = libui_ffi::uiTableValueInt(value);
+                    TableValue::Int(i)
+                }
+                TableValueType::String => {
+                    let s = libui_ffi::uiTableValueString(value);
+                    TableValue::String(CStr::from_ptr(s).to_string_lossy().into_owned())
+                }
+                TableValueType::Color => {
+                    let (mut r, mut g, mut b, mut a) = (0.0, 0.0, 0.0, 0.0);
+                    libui_ffi::uiTableValueColor(value, &mut r, &mut g, &mut b, &mut a);
+                    TableValue::Color { r, g, b, a }
+                }
+                _ => panic!("Unsupported table value type"),
+            };
+            (*(ui_handler as *mut RustTableModelHandler))
+                .trait_object
+                .borrow_mut()
+                .set_cell(column, row, rust_value)
+        });
     }
 }
 
@@ -206,6 +234,8 @@ impl RustTableModelHandler {
 pub struct TableModel {
     ui_table_model: *mut libui_ffi::uiTableModel,
     _model_handler: Box<RustTableModelHandler>,
+    // The libUI session the model was created in.
+    session: usize,
 }
 
 impl TableModel {
@@ -218,6 +248,7 @@ impl TableModel {
                     ptr as *mut libui_ffi::uiTableModelHandler,
                 ),
                 _model_handler: handler, // We store the object to bind its lifetime to ours.
+                session: ffi_tools::current_session(),
             }
         }
     }
@@ -255,6 +286,11 @@ impl TableModel {
 
 impl Drop for TableModel {
     fn drop(&mut self) {
+        // Freeing a model which outlived its session would call into libUI after it was
+        // uninitialized, so it is leaked instead.
+        if self.session != ffi_tools::current_session() {
+            return;
+        }
         unsafe {
             libui_ffi::uiFreeTableModel(self.ui_table_model);
         }
@@ -390,15 +426,12 @@ impl Table {
                 Model: params.model.borrow().ui_table_model,
                 RowBackgroundColorModelColumn: params.row_background_color_column,
             };
-            // TODO: keep the model alive via the shared ptr. This requires storing it tho.
-            // Because i am lazy, i leak the model for now.
-            mem::forget(params.model);
-            Table {
-                // The parameter struct is not stored. we can safely provide
-                // a raw pointer and let the struct go out of scope. Only the
-                // uiTableModel inside must be kept alive.
-                uiTable: libui_ffi::uiNewTable(&mut ui_params as *mut uiTableParams),
-            }
+            // The parameter struct is not stored. we can safely provide
+            // a raw pointer and let the struct go out of scope. Only the
+            // uiTableModel inside must be kept alive, until the table is destroyed.
+            let table = Table::from_raw(libui_ffi::uiNewTable(&mut ui_params as *mut uiTableParams));
+            ownership::keep_alive(table.ptr() as *mut uiControl, Box::new(params.model));
+            table
         }
     }
 
@@ -666,33 +699,60 @@ impl Table {
         where
             G: FnMut(&mut Table),
         {
-            let mut table = Table { uiTable: table };
+            let mut table = unsafe { Table::from_raw(table) };
             unsafe {
-                from_void_ptr::<G>(data)(&mut table);
+                invoke("Table", "selection_changed", || from_void_ptr::<G>(data)(&mut table));
             }
         }
         unsafe {
             libui_ffi::uiTableOnSelectionChanged(
                 self.uiTable,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiTable,
+                    "selection_changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
 
-    extern "C" fn generic_table_callback<G>(
+    fn generic_table_callback<G>(
+        event: &'static str,
         table: *mut uiTable,
         row_or_column: i32,
         data: *mut c_void,
     ) where
         G: FnMut(&mut Table, i32),
     {
-        let mut table = Table { uiTable: table };
+        let mut table = unsafe { Table::from_raw(table) };
         unsafe {
-            from_void_ptr::<G>(data)(&mut table, row_or_column);
+            invoke("Table", event, || from_void_ptr::<G>(data)(&mut table, row_or_column));
         }
     }
 
+    extern "C" fn row_clicked_callback<G>(table: *mut uiTable, row: i32, data: *mut c_void)
+    where
+        G: FnMut(&mut Table, i32),
+    {
+        Self::generic_table_callback::<G>("row_clicked", table, row, data)
+    }
+
+    extern "C" fn row_double_clicked_callback<G>(table: *mut uiTable, row: i32, data: *mut c_void)
+    where
+        G: FnMut(&mut Table, i32),
+    {
+        Self::generic_table_callback::<G>("row_double_clicked", table, row, data)
+    }
+
+    extern "C" fn header_clicked_callback<G>(table: *mut uiTable, column: i32, data: *mut c_void)
+    where
+        G: FnMut(&mut Table, i32),
+    {
+        Self::generic_table_callback::<G>("header_clicked", table, column, data)
+    }
+
     /// Registers a callback for when the user single clicks a table row.
     ///
     /// Note: Only one callback can be registered at a time.
@@ -703,8 +763,13 @@ impl Table {
         unsafe {
             libui_ffi::uiTableOnRowClicked(
                 self.uiTable,
-                Some(Self::generic_table_callback::<F>),
-                to_heap_ptr(callback),
+                Some(Self::row_clicked_callback::<F>),
+                register_callback(
+                    self.uiTable,
+                    "row_clicked",
+                    Self::row_clicked_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -722,8 +787,13 @@ impl Table {
         unsafe {
             libui_ffi::uiTableOnRowDoubleClicked(
                 self.uiTable,
-                Some(Self::generic_table_callback::<F>),
-                to_heap_ptr(callback),
+                Some(Self::row_double_clicked_callback::<F>),
+                register_callback(
+                    self.uiTable,
+                    "row_double_clicked",
+                    Self::row_double_clicked_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -738,8 +808,13 @@ impl Table {
         unsafe {
             libui_ffi::uiTableHeaderOnClicked(
                 self.uiTable,
-                Some(Self::generic_table_callback::<F>),
-                to_heap_ptr(callback),
+                Some(Self::header_clicked_callback::<F>),
+                register_callback(
+                    self.uiTable,
+                    "header_clicked",
+                    Self::header_clicked_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
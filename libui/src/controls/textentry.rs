@@ -4,7 +4,7 @@
 //! `\r\n` for display are added and removed by the controls.
 
 use super::Control;
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use libui_ffi::{self, uiControl, uiEntry, uiMultilineEntry};
 use std::ffi::{CStr, CString};
 use std::mem;
@@ -105,11 +105,20 @@ impl TextEntry for Entry {
             let string = unsafe { CStr::from_ptr(libui_ffi::uiEntryText(entry)) }
                 .to_string_lossy()
                 .into_owned();
-            unsafe { from_void_ptr::<G>(data)(string) }
+            unsafe { invoke("Entry", "changed", || from_void_ptr::<G>(data)(string)); }
         }
 
         unsafe {
-            libui_ffi::uiEntryOnChanged(self.uiEntry, Some(c_callback::<F>), to_heap_ptr(callback));
+            libui_ffi::uiEntryOnChanged(
+                self.uiEntry,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiEntry,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
         }
     }
 }
@@ -130,19 +139,20 @@ impl TextEntry for PasswordEntry {
 
     fn on_changed<'ctx, F: FnMut(String) + 'static>(&mut self, callback: F) {
         unsafe {
-            let mut data: Box<Box<dyn FnMut(String)>> = Box::new(Box::new(callback));
+            let data: Box<dyn FnMut(String)> = Box::new(callback);
             libui_ffi::uiEntryOnChanged(
                 self.uiEntry,
                 Some(c_callback),
-                &mut *data as *mut Box<dyn FnMut(String)> as *mut c_void,
+                register_callback(self.uiEntry, "changed", c_callback as *const (), data),
             );
-            mem::forget(data);
         }
 
         extern "C" fn c_callback(entry: *mut uiEntry, data: *mut c_void) {
             unsafe {
                 let string = from_toolkit_string(libui_ffi::uiEntryText(entry));
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(String)>>(data)(string);
+                invoke("PasswordEntry", "changed", || {
+                    from_void_ptr::<Box<dyn FnMut(String)>>(data)(string)
+                });
                 mem::forget(entry);
             }
         }
@@ -171,11 +181,20 @@ impl TextEntry for SearchEntry {
             let string = unsafe { CStr::from_ptr(libui_ffi::uiEntryText(entry)) }
                 .to_string_lossy()
                 .into_owned();
-            unsafe { from_void_ptr::<G>(data)(string) }
+            unsafe { invoke("SearchEntry", "changed", || from_void_ptr::<G>(data)(string)); }
         }
 
         unsafe {
-            libui_ffi::uiEntryOnChanged(self.uiEntry, Some(c_callback::<F>), to_heap_ptr(callback));
+            libui_ffi::uiEntryOnChanged(
+                self.uiEntry,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiEntry,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
         }
     }
 }
@@ -201,14 +220,19 @@ impl TextEntry for MultilineEntry {
             let string = unsafe { CStr::from_ptr(libui_ffi::uiMultilineEntryText(entry)) }
                 .to_string_lossy()
                 .into_owned();
-            unsafe { from_void_ptr::<G>(data)(string) }
+            unsafe { invoke("MultilineEntry", "changed", || from_void_ptr::<G>(data)(string)); }
         }
 
         unsafe {
             libui_ffi::uiMultilineEntryOnChanged(
                 self.uiMultilineEntry,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.uiMultilineEntry,
+                    "changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -0,0 +1,373 @@
+//! Comboboxes and radio buttons bound to a list of Rust values or an enum.
+
+use super::{Combobox, Control, LayoutStrategy, RadioButtons, VerticalBox};
+use callback_helpers::{CallbackSlot, SharedCallback};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::{Rc, Weak};
+
+type Items<T> = Rc<RefCell<Vec<T>>>;
+
+/// Returns the text shown to the user for an item.
+type LabelFn<T> = Rc<dyn Fn(&T) -> String>;
+
+/// Returns the index of `item` as used by libui, or `-1` if it is not in the list.
+fn index_of<T: PartialEq>(items: &Items<T>, item: &T) -> i32 {
+    items
+        .borrow()
+        .iter()
+        .position(|candidate| candidate == item)
+        .map_or(-1, |index| index as i32)
+}
+
+/// Returns the item at an index as used by libui, where `-1` stands for no item.
+fn item_at<T: Clone>(items: &Items<T>, index: i32) -> Option<T> {
+    if index < 0 {
+        None
+    } else {
+        items.borrow().get(index as usize).cloned()
+    }
+}
+
+/// Returns a callback for the `on_selected` event of the underlying control, which runs the
+/// user's callback with the selected item.
+fn selected_callback<T: Clone + 'static>(
+    items: &Items<T>,
+    slot: Rc<CallbackSlot<T>>,
+) -> impl FnMut(i32) + 'static {
+    let items = items.clone();
+    move |index| {
+        // Cloned, so the callback may replace the items.
+        if let Some(item) = item_at(&items, index) {
+            slot.call(item);
+        }
+    }
+}
+
+/// A type with a fixed list of values to choose from, such as an enum whose variants are
+/// offered in a [`Combobox`](struct.Combobox.html) or [`RadioButtons`](struct.RadioButtons.html).
+///
+/// With the `derive` feature, it can be derived for enums whose variants have no fields. The
+/// variants are offered in the order they are declared, labelled with their name unless a
+/// label is given with `#[choice(label = "...")]`. As the mapping between indices and variants
+/// is generated, adding a variant adds it to the controls as well.
+///
+/// # Example
+///
+/// ```no_run
+/// # extern crate libui;
+/// # extern crate libui_derive;
+/// use libui::controls::*;
+/// use libui::prelude::*;
+/// # use libui_derive::Choice;
+///
+/// #[derive(Clone, Copy, PartialEq, Choice)]
+/// enum Quality {
+///     Low,
+///     #[choice(label = "Very high")]
+///     VeryHigh,
+/// }
+///
+/// # fn main() {
+/// let ui = UI::init().unwrap();
+/// let mut quality = Quality::combobox();
+/// quality.set_selected(&Quality::VeryHigh);
+/// quality.on_selected(|quality| println!("Quality: {}", quality.label()));
+/// # }
+/// ```
+pub trait Choice: Clone + PartialEq + 'static {
+    /// Returns the value at `index`, or `None` if there are not as many values.
+    fn from_index(index: usize) -> Option<Self>;
+
+    /// Returns the index of the value.
+    fn index(&self) -> usize;
+
+    /// Returns the text shown to the user for the value.
+    fn label(&self) -> &'static str;
+
+    /// Returns all values, ordered by their index.
+    fn values() -> Vec<Self> {
+        (0..).map_while(Self::from_index).collect()
+    }
+
+    /// Creates a combobox offering all values, none of which is selected.
+    fn combobox() -> TypedCombobox<Self> {
+        TypedCombobox::with_labels(Self::values(), |value| value.label().to_string())
+    }
+
+    /// Creates radio buttons offering all values, none of which is selected.
+    fn radio_buttons() -> TypedRadioButtons<Self> {
+        TypedRadioButtons::with_labels(Self::values(), |value| value.label().to_string())
+    }
+}
+
+#[cfg(feature = "derive")]
+pub use libui_derive::Choice;
+
+fn boxed_callback<T, F: FnMut(&T) + 'static>(mut callback: F) -> Box<dyn FnMut(T)> {
+    Box::new(move |item: T| callback(&item))
+}
+
+/// A [`Combobox`](struct.Combobox.html) whose options are values of type `T`, shown using
+/// their `Display` implementation or a label function. See [`Choice`](trait.Choice.html) to
+/// offer the variants of an enum.
+///
+/// # Example
+///
+/// ```no_run
+/// use libui::controls::*;
+/// use libui::prelude::*;
+/// use std::fmt;
+///
+/// #[derive(Clone, PartialEq)]
+/// enum Quality {
+///     Low,
+///     High,
+/// }
+///
+/// impl fmt::Display for Quality {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         match self {
+///             Quality::Low => write!(f, "Low"),
+///             Quality::High => write!(f, "High"),
+///         }
+///     }
+/// }
+///
+/// let ui = UI::init().unwrap();
+/// let mut quality = TypedCombobox::new(vec![Quality::Low, Quality::High]);
+/// quality.set_selected(&Quality::High);
+/// quality.on_selected(|quality| println!("Quality: {}", quality));
+/// ```
+pub struct TypedCombobox<T> {
+    combobox: Combobox,
+    items: Items<T>,
+    label: LabelFn<T>,
+    on_selected: SharedCallback<T>,
+}
+
+impl<T> Clone for TypedCombobox<T> {
+    fn clone(&self) -> TypedCombobox<T> {
+        TypedCombobox {
+            combobox: self.combobox.clone(),
+            items: self.items.clone(),
+            label: self.label.clone(),
+            on_selected: self.on_selected.clone(),
+        }
+    }
+}
+
+impl<T> From<TypedCombobox<T>> for Control {
+    fn from(combobox: TypedCombobox<T>) -> Control {
+        combobox.combobox.into()
+    }
+}
+
+impl<T> TypedCombobox<T>
+where
+    T: Display + Clone + PartialEq + 'static,
+{
+    /// Creates a combobox with an option for each item. No item is selected.
+    pub fn new(items: Vec<T>) -> TypedCombobox<T> {
+        TypedCombobox::with_labels(items, |item| item.to_string())
+    }
+}
+
+impl<T> TypedCombobox<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    /// Creates a combobox with an option for each item, labelled by `label`. No item is
+    /// selected.
+    pub fn with_labels<F>(items: Vec<T>, label: F) -> TypedCombobox<T>
+    where
+        F: Fn(&T) -> String + 'static,
+    {
+        let mut combobox = TypedCombobox {
+            combobox: Combobox::new(),
+            items: Rc::new(RefCell::new(Vec::new())),
+            label: Rc::new(label),
+            on_selected: Rc::new(RefCell::new(None)),
+        };
+        let slot = Rc::new(CallbackSlot(combobox.on_selected.clone()));
+        let callback = selected_callback(&combobox.items, slot);
+        combobox.combobox.set_on_selected(callback);
+        combobox.set_items(items);
+        combobox
+    }
+
+    /// Returns the items of the combobox.
+    pub fn items(&self) -> Vec<T> {
+        self.items.borrow().clone()
+    }
+
+    /// Replaces the items of the combobox. The selected item stays selected if it is among the
+    /// new items.
+    pub fn set_items(&mut self, items: Vec<T>) {
+        let selected = self.selected();
+        self.combobox.clear();
+        for item in &items {
+            self.combobox.append(&(self.label)(item));
+        }
+        *self.items.borrow_mut() = items;
+        let index = selected.map_or(-1, |selected| index_of(&self.items, &selected));
+        self.combobox.set_selected(index);
+    }
+
+    /// Returns the selected item, or `None` if no item is selected.
+    pub fn selected(&self) -> Option<T> {
+        item_at(&self.items, self.combobox.selected())
+    }
+
+    /// Selects the item, or clears the selection if it is not among the items.
+    pub fn set_selected(&mut self, item: &T) {
+        let index = index_of(&self.items, item);
+        self.combobox.set_selected(index);
+    }
+
+    /// Sets a callback to be run with the item the user selected.
+    pub fn on_selected<F: FnMut(&T) + 'static>(&mut self, callback: F) {
+        *self.on_selected.borrow_mut() = Some(boxed_callback(callback));
+    }
+
+    /// Returns the underlying combobox.
+    pub fn combobox(&self) -> Combobox {
+        self.combobox.clone()
+    }
+}
+
+/// [`RadioButtons`](struct.RadioButtons.html) for values of type `T`, shown using their
+/// `Display` implementation or a label function. See [`Choice`](trait.Choice.html) to offer
+/// the variants of an enum.
+///
+/// As libui can not remove radio buttons, replacing the items replaces the underlying
+/// `RadioButtons` within a container.
+pub struct TypedRadioButtons<T> {
+    container: VerticalBox,
+    radio_buttons: Rc<RefCell<RadioButtons>>,
+    items: Items<T>,
+    label: LabelFn<T>,
+    on_selected: SharedCallback<T>,
+    // Shared by the callbacks of all underlying radio buttons, so replacing them keeps the
+    // user callback.
+    slot: Weak<CallbackSlot<T>>,
+}
+
+impl<T> Clone for TypedRadioButtons<T> {
+    fn clone(&self) -> TypedRadioButtons<T> {
+        TypedRadioButtons {
+            container: self.container.clone(),
+            radio_buttons: self.radio_buttons.clone(),
+            items: self.items.clone(),
+            label: self.label.clone(),
+            on_selected: self.on_selected.clone(),
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+impl<T> From<TypedRadioButtons<T>> for Control {
+    fn from(radio_buttons: TypedRadioButtons<T>) -> Control {
+        radio_buttons.container.into()
+    }
+}
+
+impl<T> TypedRadioButtons<T>
+where
+    T: Display + Clone + PartialEq + 'static,
+{
+    /// Creates radio buttons with a button for each item. No item is selected.
+    pub fn new(items: Vec<T>) -> TypedRadioButtons<T> {
+        TypedRadioButtons::with_labels(items, |item| item.to_string())
+    }
+}
+
+impl<T> TypedRadioButtons<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    /// Creates radio buttons with a button for each item, labelled by `label`. No item is
+    /// selected.
+    pub fn with_labels<F>(items: Vec<T>, label: F) -> TypedRadioButtons<T>
+    where
+        F: Fn(&T) -> String + 'static,
+    {
+        let mut radio_buttons = TypedRadioButtons {
+            container: VerticalBox::new(),
+            radio_buttons: Rc::new(RefCell::new(RadioButtons::new())),
+            items: Rc::new(RefCell::new(Vec::new())),
+            label: Rc::new(label),
+            on_selected: Rc::new(RefCell::new(None)),
+            slot: Weak::new(),
+        };
+        let native = radio_buttons.radio_buttons();
+        radio_buttons
+            .container
+            .append(native, LayoutStrategy::Compact);
+        radio_buttons.set_items(items);
+        radio_buttons
+    }
+
+    /// Returns the items of the radio buttons.
+    pub fn items(&self) -> Vec<T> {
+        self.items.borrow().clone()
+    }
+
+    /// Replaces the items of the radio buttons. The selected item stays selected if it is
+    /// among the new items.
+    pub fn set_items(&mut self, items: Vec<T>) {
+        let selected = self.selected();
+        let mut native = RadioButtons::new();
+        for item in &items {
+            native.append(&(self.label)(item));
+        }
+        *self.items.borrow_mut() = items;
+        let slot = self
+            .slot
+            .upgrade()
+            .unwrap_or_else(|| Rc::new(CallbackSlot(self.on_selected.clone())));
+        self.slot = Rc::downgrade(&slot);
+        native.set_on_selected(selected_callback(&self.items, slot));
+        native.set_selected(selected.map_or(-1, |selected| index_of(&self.items, &selected)));
+
+        let _ = self.container.delete(0);
+        self.container.append(native.clone(), LayoutStrategy::Compact);
+        *self.radio_buttons.borrow_mut() = native;
+    }
+
+    /// Returns the selected item, or `None` if no item is selected.
+    pub fn selected(&self) -> Option<T> {
+        item_at(&self.items, self.radio_buttons.borrow().selected())
+    }
+
+    /// Selects the item, or clears the selection if it is not among the items.
+    pub fn set_selected(&mut self, item: &T) {
+        let index = index_of(&self.items, item);
+        self.radio_buttons.borrow_mut().set_selected(index);
+    }
+
+    /// Sets a callback to be run with the item the user selected.
+    pub fn on_selected<F: FnMut(&T) + 'static>(&mut self, callback: F) {
+        *self.on_selected.borrow_mut() = Some(boxed_callback(callback));
+    }
+
+    /// Returns the underlying radio buttons. They are replaced when the items are.
+    pub fn radio_buttons(&self) -> RadioButtons {
+        self.radio_buttons.borrow().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indices_map_onto_items() {
+        let items: Items<&str> = Rc::new(RefCell::new(vec!["red", "green", "blue"]));
+        assert_eq!(index_of(&items, &"green"), 1);
+        assert_eq!(index_of(&items, &"black"), -1);
+        assert_eq!(item_at(&items, 2), Some("blue"));
+        assert_eq!(item_at(&items, 3), None);
+        assert_eq!(item_at(&items, -1), None);
+    }
+}
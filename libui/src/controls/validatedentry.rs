@@ -0,0 +1,137 @@
+//! A text entry bound to a Rust type.
+
+use super::{Control, Entry, Label, LayoutStrategy, TextEntry, VerticalBox};
+use callback_helpers::{CallbackSlot, SharedCallback};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// A single-line entry whose text is parsed into a `T` on every change.
+///
+/// It consists of an [`Entry`](struct.Entry.html) and a [`Label`](struct.Label.html) below it,
+/// which shows why the text could not be parsed and is hidden while the text is valid. Like
+/// any other control, it can be added to a container.
+///
+/// # Example
+///
+/// ```no_run
+/// use libui::controls::*;
+/// use libui::prelude::*;
+///
+/// let ui = UI::init().unwrap();
+/// let mut port: ValidatedEntry<u16> = ValidatedEntry::new();
+/// port.set_value(&8080);
+/// port.on_valid_change(|port| println!("Listening on port {}", port));
+///
+/// let mut layout = VerticalBox::new();
+/// layout.append(port.clone(), LayoutStrategy::Compact);
+/// ```
+pub struct ValidatedEntry<T> {
+    container: VerticalBox,
+    entry: Entry,
+    error: Label,
+    on_valid_change: SharedCallback<T>,
+}
+
+impl<T> Clone for ValidatedEntry<T> {
+    fn clone(&self) -> ValidatedEntry<T> {
+        ValidatedEntry {
+            container: self.container.clone(),
+            entry: self.entry.clone(),
+            error: self.error.clone(),
+            on_valid_change: self.on_valid_change.clone(),
+        }
+    }
+}
+
+impl<T> From<ValidatedEntry<T>> for Control {
+    fn from(entry: ValidatedEntry<T>) -> Control {
+        entry.container.into()
+    }
+}
+
+impl<T> Default for ValidatedEntry<T>
+where
+    T: FromStr + Display + 'static,
+    T::Err: Display,
+{
+    fn default() -> ValidatedEntry<T> {
+        ValidatedEntry::new()
+    }
+}
+
+impl<T> ValidatedEntry<T>
+where
+    T: FromStr + Display + 'static,
+    T::Err: Display,
+{
+    /// Creates a new, empty entry. No error is shown until the text is changed.
+    pub fn new() -> ValidatedEntry<T> {
+        let mut container = VerticalBox::new();
+        let mut entry = Entry::new();
+        let mut error = Label::new("");
+        error.hide();
+        let on_valid_change: SharedCallback<T> = Rc::new(RefCell::new(None));
+
+        entry.on_changed({
+            let mut error = error.clone();
+            let slot = CallbackSlot(on_valid_change.clone());
+            move |text| match text.parse::<T>() {
+                Ok(value) => {
+                    error.hide();
+                    error.set_text("");
+                    slot.call(value);
+                }
+                Err(err) => {
+                    error.set_text(&err.to_string());
+                    error.show();
+                }
+            }
+        });
+
+        container.append(entry.clone(), LayoutStrategy::Compact);
+        container.append(error.clone(), LayoutStrategy::Compact);
+        ValidatedEntry {
+            container,
+            entry,
+            error,
+            on_valid_change,
+        }
+    }
+
+    /// Parses the current text of the entry.
+    pub fn value(&self) -> Result<T, T::Err> {
+        self.entry.value().parse()
+    }
+
+    /// Shows the value in the entry and hides any error. The `on_valid_change` callback is not
+    /// run.
+    pub fn set_value(&mut self, value: &T) {
+        self.entry.set_value(&value.to_string());
+        self.error.hide();
+        self.error.set_text("");
+    }
+
+    /// Returns the error shown below the entry, or `None` if the text is valid or was not
+    /// changed yet.
+    pub fn error(&self) -> Option<String> {
+        let error = self.error.text();
+        if error.is_empty() {
+            None
+        } else {
+            Some(error)
+        }
+    }
+
+    /// Sets a callback to be run with the parsed value whenever the user changes the text to
+    /// something which parses. Changes to invalid text only show the error.
+    pub fn on_valid_change<F: FnMut(T) + 'static>(&mut self, callback: F) {
+        *self.on_valid_change.borrow_mut() = Some(Box::new(callback));
+    }
+
+    /// Returns the underlying entry, for example to disable it.
+    pub fn entry(&self) -> Entry {
+        self.entry.clone()
+    }
+}
@@ -1,12 +1,14 @@
 //! Functionality related to creating, managing, and destroying GUI windows.
 
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback, registered_callback};
 use controls::Control;
 use std::cell::RefCell;
 use std::ffi::{CStr, CString};
 use std::mem;
 use std::os::raw::{c_int, c_void};
 use std::path::PathBuf;
+use std::ptr;
+use ownership;
 use ui::UI;
 use libui_ffi::{self, uiControl, uiFreeText, uiWindow};
 
@@ -14,6 +16,17 @@ thread_local! {
     static WINDOWS: RefCell<Vec<Window>> = RefCell::new(Vec::new())
 }
 
+/// What should happen to a window after its `on_closing` callback has run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseAction {
+    /// Keep the window open.
+    Keep,
+    /// Close and destroy the window, like [`Window::destroy`](struct.Window.html#method.destroy).
+    Destroy,
+    /// Hide the window, so it can be shown again later.
+    Hide,
+}
+
 /// A `Window` can either have a menubar or not; this enum represents that decision.
 #[derive(Clone, Copy, Debug)]
 pub enum WindowType {
@@ -32,6 +45,11 @@ impl Window {
     /// By default, when a new window is created, it will cause the application to quit when closed.
     /// The user can prevent this by adding a custom `on_closing` behavior.
     pub fn new(_ctx: &UI, title: &str, width: c_int, height: c_int, t: WindowType) -> Window {
+        Window::create(title, width, height, t)
+    }
+
+    /// Creates a new window like `new`, for use within the crate where no `UI` is at hand.
+    pub(crate) fn create(title: &str, width: c_int, height: c_int, t: WindowType) -> Window {
         let has_menubar = match t {
             WindowType::HasMenubar => true,
             WindowType::NoMenubar => false,
@@ -50,10 +68,11 @@ impl Window {
             window
         };
 
-        // Windows, by default, quit the application on closing.
-        let ui = _ctx.clone();
-        window.on_closing(_ctx, move |_| {
-            ui.quit();
+        // Windows, by default, quit the application on closing. The callback must not hold on
+        // to the `UI`, or it would never be dropped.
+        window.set_on_closing(|_| {
+            unsafe { libui_ffi::uiQuit() };
+            CloseAction::Keep
         });
 
         // Windows, by default, draw margins
@@ -84,30 +103,140 @@ impl Window {
         }
     }
 
-    /// Set a callback to be run when the window closes.
+    /// Set a callback to be run when the user tries to close the window.
     ///
-    /// This is often used on the main window of an application to quit
+    /// The returned [`CloseAction`](enum.CloseAction.html) decides whether the window is kept
+    /// open, hidden or destroyed. This is often used on the main window of an application to quit
     /// the application when the window is closed.
     pub fn on_closing<'ctx, F>(&mut self, _ctx: &'ctx UI, callback: F)
     where
-        F: FnMut(&mut Window) + 'static,
+        F: FnMut(&mut Window) -> CloseAction + 'static,
+    {
+        self.set_on_closing(callback)
+    }
+
+    /// Sets the `on_closing` callback, for use within the crate where no `UI` is at hand.
+    pub(crate) fn set_on_closing<F>(&mut self, callback: F)
+    where
+        F: FnMut(&mut Window) -> CloseAction + 'static,
     {
         extern "C" fn c_callback<G>(window: *mut uiWindow, data: *mut c_void) -> i32
         where
-            G: FnMut(&mut Window),
+            G: FnMut(&mut Window) -> CloseAction,
         {
-            let mut window = Window { uiWindow: window };
-            unsafe {
-                from_void_ptr::<G>(data)(&mut window);
+            let mut window = unsafe { Window::from_raw(window) };
+            let action = unsafe { invoke("Window", "closing", || from_void_ptr::<G>(data)(&mut window)) }
+                .unwrap_or(CloseAction::Keep);
+            match action {
+                CloseAction::Keep => 0,
+                CloseAction::Hide => {
+                    window.hide();
+                    0
+                }
+                CloseAction::Destroy => {
+                    // Rather than libui, the last handle destroys the window, so handles held
+                    // elsewhere stay valid.
+                    window.clone().destroy();
+                    0
+                }
             }
-            0
         }
 
         unsafe {
-            libui_ffi::uiWindowOnClosing(self.uiWindow, Some(c_callback::<F>), to_heap_ptr(callback));
+            libui_ffi::uiWindowOnClosing(
+                self.uiWindow,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiWindow,
+                    "closing",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
+        }
+    }
+
+    /// Asks the window to close, as if the user clicked its close button.
+    ///
+    /// The `on_closing` callback is run and its [`CloseAction`](enum.CloseAction.html) is
+    /// followed. Returns `true` if the window was destroyed.
+    pub fn close(&mut self) -> bool {
+        if !self.is_alive() {
+            return false;
+        }
+        match registered_callback(self.uiWindow, "closing") {
+            Some((trampoline, data)) => {
+                let trampoline: extern "C" fn(*mut uiWindow, *mut c_void) -> c_int =
+                    unsafe { mem::transmute(trampoline) };
+                // Our callbacks destroy the window themselves and never leave it to libui.
+                trampoline(self.uiWindow, data);
+                !self.is_alive()
+            }
+            None => false,
         }
     }
 
+    /// Returns `true` until the window is destroyed, whether by closing it, by `destroy` or
+    /// by [`Control::destroy`](struct.Control.html#method.destroy).
+    pub fn is_alive(&self) -> bool {
+        WINDOWS.with(|windows| {
+            windows.borrow().iter().any(|window| {
+                window.uiWindow == self.uiWindow && window.generation == self.generation
+            })
+        })
+    }
+
+    /// Sets a callback to be run when the window is destroyed, whether by closing it, by
+    /// `destroy` or by [`Control::destroy`](struct.Control.html#method.destroy). It is not run
+    /// for the windows left when the `UI` is dropped.
+    ///
+    /// # Example
+    ///
+    /// Keeping track of the open documents:
+    ///
+    /// ```no_run
+    /// use libui::prelude::*;
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let documents = Rc::new(RefCell::new(Vec::new()));
+    ///
+    /// let mut window = Window::new(&ui, "Untitled", 640, 480, WindowType::HasMenubar);
+    /// window.on_closing(&ui, |_| CloseAction::Destroy);
+    /// window.on_destroyed({
+    ///     let documents = documents.clone();
+    ///     move |window| {
+    ///         documents
+    ///             .borrow_mut()
+    ///             .retain(|document: &Window| document.ptr() != window.ptr())
+    ///     }
+    /// });
+    /// documents.borrow_mut().push(window.clone());
+    /// ```
+    pub fn on_destroyed<F>(&mut self, callback: F)
+    where
+        F: FnMut(&mut Window) + 'static,
+    {
+        // Called by `take_registered` rather than by libui.
+        extern "C" fn c_callback<G>(window: *mut uiWindow, data: *mut c_void)
+        where
+            G: FnMut(&mut Window),
+        {
+            let mut window = unsafe { Window::from_raw(window) };
+            unsafe {
+                invoke("Window", "destroyed", || from_void_ptr::<G>(data)(&mut window));
+            }
+        }
+
+        register_callback(
+            self.uiWindow,
+            "destroyed",
+            c_callback::<F> as *const (),
+            callback,
+        );
+    }
+
     /// Gets the window position on the screen.
     /// Coordinates are measured from the top-left corner of the screen.
     /// 
@@ -140,14 +269,122 @@ impl Window {
         where
             G: FnMut(&mut Window),
         {
-            let mut window = Window { uiWindow: window };
+            let mut window = unsafe { Window::from_raw(window) };
             unsafe {
-                from_void_ptr::<G>(data)(&mut window);
+                invoke("Window", "position_changed", || from_void_ptr::<G>(data)(&mut window));
             }
         }
 
         unsafe {
-            libui_ffi::uiWindowOnPositionChanged(self.uiWindow, Some(c_callback::<F>), to_heap_ptr(callback));
+            libui_ffi::uiWindowOnPositionChanged(
+                self.uiWindow,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiWindow,
+                    "position_changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
+        }
+    }
+
+    /// Gets the size of the window's content area, excluding its borders, title bar and menubar.
+    pub fn content_size(&self) -> (i32, i32) {
+        let mut width: c_int = 0;
+        let mut height: c_int = 0;
+        unsafe { libui_ffi::uiWindowContentSize(self.uiWindow, &mut width, &mut height) }
+
+        (width, height)
+    }
+
+    /// Resizes the window so that its content area has the given size.
+    pub fn set_content_size(&mut self, width: i32, height: i32) {
+        unsafe { libui_ffi::uiWindowSetContentSize(self.uiWindow, width, height) }
+    }
+
+    /// Sets a callback to be run when the size of the window's content area changes, such as when
+    /// the user resizes the window.
+    pub fn on_content_size_changed<F>(&mut self, callback: F)
+    where
+        F: FnMut(&mut Window) + 'static,
+    {
+        extern "C" fn c_callback<G>(window: *mut uiWindow, data: *mut c_void)
+        where
+            G: FnMut(&mut Window),
+        {
+            let mut window = unsafe { Window::from_raw(window) };
+            unsafe {
+                invoke("Window", "content_size_changed", || from_void_ptr::<G>(data)(&mut window));
+            }
+        }
+
+        unsafe {
+            libui_ffi::uiWindowOnContentSizeChanged(
+                self.uiWindow,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiWindow,
+                    "content_size_changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
+        }
+    }
+
+    /// Check whether or not this window covers the whole screen.
+    pub fn fullscreen(&self) -> bool {
+        unsafe { libui_ffi::uiWindowFullscreen(self.uiWindow) != 0 }
+    }
+
+    /// Set whether or not this window covers the whole screen.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        unsafe { libui_ffi::uiWindowSetFullscreen(self.uiWindow, fullscreen as c_int) }
+    }
+
+    /// Check whether or not this window is drawn without borders and title bar.
+    pub fn borderless(&self) -> bool {
+        unsafe { libui_ffi::uiWindowBorderless(self.uiWindow) != 0 }
+    }
+
+    /// Set whether or not this window is drawn without borders and title bar.
+    pub fn set_borderless(&mut self, borderless: bool) {
+        unsafe { libui_ffi::uiWindowSetBorderless(self.uiWindow, borderless as c_int) }
+    }
+
+    /// Check whether or not this window has the keyboard focus.
+    pub fn focused(&self) -> bool {
+        unsafe { libui_ffi::uiWindowFocused(self.uiWindow) != 0 }
+    }
+
+    /// Sets a callback to be run when the window gains or loses the keyboard focus. Use
+    /// `focused` to tell which of the two happened.
+    pub fn on_focus_changed<F>(&mut self, callback: F)
+    where
+        F: FnMut(&mut Window) + 'static,
+    {
+        extern "C" fn c_callback<G>(window: *mut uiWindow, data: *mut c_void)
+        where
+            G: FnMut(&mut Window),
+        {
+            let mut window = unsafe { Window::from_raw(window) };
+            unsafe {
+                invoke("Window", "focus_changed", || from_void_ptr::<G>(data)(&mut window));
+            }
+        }
+
+        unsafe {
+            libui_ffi::uiWindowOnFocusChanged(
+                self.uiWindow,
+                Some(c_callback::<F>),
+                register_callback(
+                    self.uiWindow,
+                    "focus_changed",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
         }
     }
 
@@ -174,11 +411,23 @@ impl Window {
     }
 
     /// Sets the window's child widget. The window can only have one child widget at a time.
+    ///
+    /// The previous child is destroyed, unless there are handles to it left.
     pub fn set_child<T: Into<Control>>(&mut self, child: T) {
-        unsafe { libui_ffi::uiWindowSetChild(self.uiWindow, child.into().as_ui_control()) }
+        unsafe fn remove_child(window: *mut uiControl, _index: c_int) {
+            libui_ffi::uiWindowSetChild(window as *mut uiWindow, ptr::null_mut())
+        }
+
+        let child = child.into();
+        let window = self.uiWindow as *mut uiControl;
+        unsafe { libui_ffi::uiWindowSetChild(self.uiWindow, child.as_ui_control()) }
+        ownership::disown_all(window);
+        ownership::adopt(window, child.as_ui_control(), None, Some(remove_child));
     }
 
     /// Allow the user to select an existing file using the systems file dialog
+    ///
+    /// See [`FileDialog`](struct.FileDialog.html) for filters and other options.
     pub fn open_file(&self) -> Option<PathBuf> {
         let ptr = unsafe { libui_ffi::uiOpenFile(self.uiWindow) };
         if ptr.is_null() {
@@ -192,6 +441,8 @@ impl Window {
     }
 
     /// Allow the user to select a new or existing file using the systems file dialog.
+    ///
+    /// See [`FileDialog`](struct.FileDialog.html) for filters and other options.
     pub fn save_file(&self) -> Option<PathBuf> {
         let ptr = unsafe { libui_ffi::uiSaveFile(self.uiWindow) };
         if ptr.is_null() {
@@ -205,6 +456,8 @@ impl Window {
     }
 
     /// Allow the user to select a single folder using the systems folder dialog.
+    ///
+    /// See [`FileDialog`](struct.FileDialog.html) for filters and other options.
     pub fn open_folder(&self) -> Option<PathBuf> {
         let ptr = unsafe { libui_ffi::uiOpenFolder(self.uiWindow) };
         if ptr.is_null() {
@@ -237,19 +490,68 @@ impl Window {
         }
     }
 
-    pub unsafe fn destroy_all_windows() {
-        WINDOWS.with(|windows| {
+    /// Returns the first window which was created and not destroyed yet.
+    pub(crate) fn first() -> Option<Window> {
+        WINDOWS.with(|windows| windows.borrow().first().cloned())
+    }
+
+    /// Removes a window from the list of live windows, which are destroyed at shutdown.
+    pub(crate) fn unregister(window: *mut uiWindow) {
+        // Dropped outside of the borrow, as this may destroy the window.
+        drop(Window::take_registered(window));
+    }
+
+    /// Removes a window from the list of live windows and runs its `on_destroyed` callback,
+    /// returning the handle held by the list.
+    fn take_registered(window: *mut uiWindow) -> Vec<Window> {
+        let removed: Vec<Window> = WINDOWS.with(|windows| {
             let mut windows = windows.borrow_mut();
-            for window in windows.drain(..) {
-                window.destroy();
+            let (removed, kept) = windows
+                .drain(..)
+                .partition(|registered| registered.uiWindow == window);
+            *windows = kept;
+            removed
+        });
+        if !removed.is_empty() {
+            if let Some((trampoline, data)) = registered_callback(window, "destroyed") {
+                let trampoline: extern "C" fn(*mut uiWindow, *mut c_void) =
+                    unsafe { mem::transmute(trampoline) };
+                trampoline(window, data);
             }
-        })
+        }
+        removed
     }
 
-    /// Destroys a Window. Any use of the control after this is use-after-free; therefore, this
-    /// is marked unsafe.
-    pub unsafe fn destroy(&self) {
-        // Don't check for initialization here since this can be run during deinitialization.
-        libui_ffi::uiControlDestroy(self.uiWindow as *mut libui_ffi::uiControl)
+    /// Returns all windows which were created and not destroyed yet, in the order they were
+    /// created.
+    pub(crate) fn all() -> Vec<Window> {
+        WINDOWS.with(|windows| windows.borrow().clone())
+    }
+
+    /// Destroys all windows. Used when uninitializing.
+    pub unsafe fn destroy_all_windows() {
+        let windows: Vec<Window> = WINDOWS.with(|windows| windows.borrow_mut().drain(..).collect());
+        for window in &windows {
+            // Destroy the windows right away, regardless of any handles left.
+            ownership::destroy(window.uiWindow as *mut uiControl);
+        }
+    }
+
+    /// Destroys the window along with its children.
+    ///
+    /// The window is hidden and its callbacks are dropped right away. It is freed once the last
+    /// handle to it is dropped.
+    pub fn destroy(mut self) {
+        Window::unregister(self.uiWindow);
+        self.hide();
+        ownership::unregister_tree_callbacks(self.uiWindow as *mut uiControl);
+    }
+}
+
+impl UI {
+    /// Returns all windows which were created and not destroyed yet, in the order they were
+    /// created.
+    pub fn windows(&self) -> Vec<Window> {
+        Window::all()
     }
 }
@@ -1,4 +1,5 @@
 use draw::{Brush, Path, StrokeParams, Transform};
+use ffi_tools;
 use libui_ffi::{self, uiDrawContext};
 
 /// Drawing context, used to draw custom content on the screen.
@@ -20,6 +21,7 @@ impl DrawContext {
 
     /// Draw a stroke on this DrawContext which runs along the given Path, with the given Brush and StrokeParams.
     pub fn stroke(&self, path: &Path, brush: &Brush, stroke_params: &StrokeParams) {
+        ffi_tools::debug_assert_gui_thread("DrawContext::stroke");
         unsafe {
             let brush = brush.as_ui_draw_brush_ref(self);
             let stroke_params = stroke_params.as_stroke_params_ref(self);
@@ -34,6 +36,7 @@ impl DrawContext {
 
     /// Draw a fill on this DrawContext using the given Path using the given Brush.
     pub fn fill(&self, path: &Path, brush: &Brush) {
+        ffi_tools::debug_assert_gui_thread("DrawContext::fill");
         unsafe {
             let brush = brush.as_ui_draw_brush_ref(self);
             libui_ffi::uiDrawFill(self.ui_draw_context, path.ptr(), brush.ptr())
@@ -42,16 +45,19 @@ impl DrawContext {
 
     /// Transform this DrawContext by the given Transform.
     pub fn transform(&self, txform: &Transform) {
+        ffi_tools::debug_assert_gui_thread("DrawContext::transform");
         unsafe { libui_ffi::uiDrawTransform(self.ui_draw_context, txform.ptr()) }
     }
 
     /// Open a modal allowing the user to save the contents of this DrawContext.
     pub fn save(&self) {
+        ffi_tools::debug_assert_gui_thread("DrawContext::save");
         unsafe { libui_ffi::uiDrawSave(self.ui_draw_context) }
     }
 
     /// Open a modal allowing the user to load the contents of a DrawContext onto this one.
     pub fn restore(&self) {
+        ffi_tools::debug_assert_gui_thread("DrawContext::restore");
         unsafe { libui_ffi::uiDrawRestore(self.ui_draw_context) }
     }
 }
@@ -1,5 +1,7 @@
 use draw::DrawContext;
+use ffi_tools;
 use std::os::raw::c_int;
+use std::thread;
 use libui_ffi::{self, uiDrawFillMode, uiDrawFillModeAlternate, uiDrawFillModeWinding, uiDrawPath};
 
 pub struct Path {
@@ -8,6 +10,9 @@ pub struct Path {
 
 impl Drop for Path {
     fn drop(&mut self) {
+        if !thread::panicking() {
+            ffi_tools::debug_assert_gui_thread("Path::drop");
+        }
         unsafe { libui_ffi::uiDrawFreePath(self.ui_draw_path) }
     }
 }
@@ -32,6 +37,7 @@ impl FillMode {
 
 impl Path {
     pub fn new(_ctx: &DrawContext, fill_mode: FillMode) -> Path {
+        ffi_tools::debug_assert_gui_thread("Path::new");
         unsafe {
             Path {
                 ui_draw_path: libui_ffi::uiDrawNewPath(fill_mode.into_ui_fillmode()),
@@ -40,6 +46,7 @@ impl Path {
     }
 
     pub fn new_figure(&self, _ctx: &DrawContext, x: f64, y: f64) {
+        ffi_tools::debug_assert_gui_thread("Path::new_figure");
         unsafe { libui_ffi::uiDrawPathNewFigure(self.ui_draw_path, x, y) }
     }
 
@@ -53,6 +60,7 @@ impl Path {
         sweep: f64,
         negative: bool,
     ) {
+        ffi_tools::debug_assert_gui_thread("Path::new_figure_with_arc");
         unsafe {
             libui_ffi::uiDrawPathNewFigureWithArc(
                 self.ui_draw_path,
@@ -67,6 +75,7 @@ impl Path {
     }
 
     pub fn line_to(&self, _ctx: &DrawContext, x: f64, y: f64) {
+        ffi_tools::debug_assert_gui_thread("Path::line_to");
         unsafe { libui_ffi::uiDrawPathLineTo(self.ui_draw_path, x, y) }
     }
 
@@ -80,6 +89,7 @@ impl Path {
         sweep: f64,
         negative: bool,
     ) {
+        ffi_tools::debug_assert_gui_thread("Path::arc_to");
         unsafe {
             libui_ffi::uiDrawPathArcTo(
                 self.ui_draw_path,
@@ -103,18 +113,22 @@ impl Path {
         end_x: f64,
         end_y: f64,
     ) {
+        ffi_tools::debug_assert_gui_thread("Path::bezier_to");
         unsafe { libui_ffi::uiDrawPathBezierTo(self.ui_draw_path, c1x, c1y, c2x, c2y, end_x, end_y) }
     }
 
     pub fn close_figure(&self, _ctx: &DrawContext) {
+        ffi_tools::debug_assert_gui_thread("Path::close_figure");
         unsafe { libui_ffi::uiDrawPathCloseFigure(self.ui_draw_path) }
     }
 
     pub fn add_rectangle(&self, _ctx: &DrawContext, x: f64, y: f64, width: f64, height: f64) {
+        ffi_tools::debug_assert_gui_thread("Path::add_rectangle");
         unsafe { libui_ffi::uiDrawPathAddRectangle(self.ui_draw_path, x, y, width, height) }
     }
 
     pub fn end(&self, _ctx: &DrawContext) {
+        ffi_tools::debug_assert_gui_thread("Path::end");
         unsafe { libui_ffi::uiDrawPathEnd(self.ui_draw_path) }
     }
 
@@ -1,3 +1,4 @@
+use ffi_tools;
 use std::mem;
 use std::ops::Mul;
 use libui_ffi::{self, uiDrawMatrix};
@@ -18,6 +19,7 @@ impl Transform {
 
     /// Create a new Transform that does nothing.
     pub fn identity() -> Transform {
+        ffi_tools::debug_assert_gui_thread("Transform::identity");
         unsafe {
             let mut matrix = mem::MaybeUninit::uninit();
             libui_ffi::uiDrawMatrixSetIdentity(matrix.as_mut_ptr());
@@ -27,31 +29,37 @@ impl Transform {
 
     /// Modify this Transform to translate by the given amounts.
     pub fn translate(&mut self, x: f64, y: f64) {
+        ffi_tools::debug_assert_gui_thread("Transform::translate");
         unsafe { libui_ffi::uiDrawMatrixTranslate(&mut self.ui_matrix, x, y) }
     }
 
     /// Modify this Transform to scale by the given amounts from the given center.
     pub fn scale(&mut self, x_center: f64, y_center: f64, x: f64, y: f64) {
+        ffi_tools::debug_assert_gui_thread("Transform::scale");
         unsafe { libui_ffi::uiDrawMatrixScale(&mut self.ui_matrix, x_center, y_center, x, y) }
     }
 
     /// Modify this Transform to rotate around the given center by the given angle.
     pub fn rotate(&mut self, x: f64, y: f64, angle: f64) {
+        ffi_tools::debug_assert_gui_thread("Transform::rotate");
         unsafe { libui_ffi::uiDrawMatrixRotate(&mut self.ui_matrix, x, y, angle) }
     }
 
     /// Modify this Transform to skew from the given point by the given amount.
     pub fn skew(&mut self, x: f64, y: f64, xamount: f64, yamount: f64) {
+        ffi_tools::debug_assert_gui_thread("Transform::skew");
         unsafe { libui_ffi::uiDrawMatrixSkew(&mut self.ui_matrix, x, y, xamount, yamount) }
     }
 
     /// Compose this Transform with another, creating a Transform which represents both operations.
     pub fn compose(&mut self, src: &Transform) {
+        ffi_tools::debug_assert_gui_thread("Transform::compose");
         unsafe { libui_ffi::uiDrawMatrixMultiply(&mut self.ui_matrix, src.ptr()) }
     }
 
     /// Returns true if inverting this Transform is possible.
     pub fn invertible(&self) -> bool {
+        ffi_tools::debug_assert_gui_thread("Transform::invertible");
         unsafe {
             libui_ffi::uiDrawMatrixInvertible(
                 &self.ui_matrix as *const uiDrawMatrix as *mut uiDrawMatrix,
@@ -61,10 +69,12 @@ impl Transform {
 
     /// Attempts to invert the Transform, returning true if it succeeded and false if it failed.
     pub fn invert(&mut self) -> bool {
+        ffi_tools::debug_assert_gui_thread("Transform::invert");
         unsafe { libui_ffi::uiDrawMatrixInvert(&mut self.ui_matrix) != 0 }
     }
 
     pub fn transform_point(&self, mut point: (f64, f64)) -> (f64, f64) {
+        ffi_tools::debug_assert_gui_thread("Transform::transform_point");
         unsafe {
             libui_ffi::uiDrawMatrixTransformPoint(
                 &self.ui_matrix as *const uiDrawMatrix as *mut uiDrawMatrix,
@@ -76,6 +86,7 @@ impl Transform {
     }
 
     pub fn transform_size(&self, mut size: (f64, f64)) -> (f64, f64) {
+        ffi_tools::debug_assert_gui_thread("Transform::transform_size");
         unsafe {
             libui_ffi::uiDrawMatrixTransformSize(
                 &self.ui_matrix as *const uiDrawMatrix as *mut uiDrawMatrix,
@@ -10,8 +10,16 @@ pub enum UIError {
     /// Signifies that an attempt was made to initialize a new instance of the underlying library while
     /// one already existed.
     MultipleInitError(),
+    /// Signifies that an attempt was made to use the underlying library after the `UI` it
+    /// belonged to was dropped.
+    NotInitializedError(),
     /// Signifies that an attempt was made to remove a tab (index) from a tab group that was out of bounds (n).
     TabGroupIndexOutOfBounds { index: i32, n: i32 },
+    /// Signifies that an attempt was made to remove a child (index) from a box that was out of bounds (n).
+    BoxIndexOutOfBounds { index: i32, n: i32 },
+    /// Signifies that an attempt was made to destroy a control whose parent does not support
+    /// removing children, such as a `LayoutGrid`.
+    ChildNotRemovableError(),
 }
 
 impl Display for UIError {
@@ -25,11 +33,22 @@ impl Display for UIError {
             UIError::MultipleInitError() => {
                 write!(f, "cannot initialize multiple instances of libui")
             }
+            UIError::NotInitializedError() => {
+                write!(f, "libui is not initialized anymore")
+            }
             UIError::TabGroupIndexOutOfBounds { index, n } => write!(
                 f,
                 "tab with index {} is not in tab group of size {}",
                 index, n
             ),
+            UIError::BoxIndexOutOfBounds { index, n } => write!(
+                f,
+                "child with index {} is not in box of size {}",
+                index, n
+            ),
+            UIError::ChildNotRemovableError() => {
+                write!(f, "control cannot be removed from its parent")
+            }
         }
     }
 }
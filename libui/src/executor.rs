@@ -0,0 +1,271 @@
+//! A minimal executor which runs futures on the GUI thread.
+//!
+//! Tasks are polled from `uiQueueMain` callbacks, so they never block the event loop.
+//! Whenever a task is woken, from any thread, it is queued to be polled again.
+
+use panics::catch_panic;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::mem;
+use std::panic;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use ui::{RemoteUI, UI};
+
+type LocalTask = Pin<Box<dyn Future<Output = ()>>>;
+
+thread_local! {
+    static TASKS: RefCell<HashMap<usize, LocalTask>> = RefCell::new(HashMap::new());
+    static NEXT_TASK_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Wakes a task by queueing a poll of it on the GUI thread.
+struct TaskWaker {
+    id: usize,
+    remote: RemoteUI,
+    scheduled: AtomicBool,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // Queue a single poll, no matter how often the task is woken in the meantime.
+        if !self.scheduled.swap(true, Ordering::SeqCst) {
+            let waker = self.clone();
+            // If the UI is gone, there is nothing left to poll the task on.
+            let _ = self.remote.queue_main(move || poll_task(waker));
+        }
+    }
+}
+
+fn poll_task(waker: Arc<TaskWaker>) {
+    waker.scheduled.store(false, Ordering::SeqCst);
+
+    // Take the task out of the registry while polling it, as it may spawn new tasks.
+    let id = waker.id;
+    let task = TASKS.with(|tasks| tasks.borrow_mut().remove(&id));
+    if let Some(mut task) = task {
+        let waker = Waker::from(waker);
+        let mut context = Context::from_waker(&waker);
+        if task.as_mut().poll(&mut context).is_pending() {
+            TASKS.with(|tasks| tasks.borrow_mut().insert(id, task));
+        }
+    }
+}
+
+/// Drops all tasks which did not complete yet.
+pub fn clear_tasks() {
+    let tasks = TASKS.with(|tasks| mem::take(&mut *tasks.borrow_mut()));
+    // Dropped outside of the borrow, as a task's destructor might access the registry.
+    drop(tasks);
+}
+
+struct JoinState<T> {
+    output: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// A handle to the output of a task started with [`UI::spawn_local`](struct.UI.html#method.spawn_local)
+/// or [`UI::spawn_blocking`](struct.UI.html#method.spawn_blocking), or of a question asked
+/// with [`Window::ask_async`](controls/struct.Window.html#method.ask_async).
+///
+/// The handle is itself a future, resolving to the task's output. Dropping it detaches the
+/// task, which then keeps running to completion.
+pub struct JoinHandle<T> {
+    state: Arc<Mutex<JoinState<T>>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Creates a handle and the handle to complete it with.
+    pub(crate) fn new() -> (JoinHandle<T>, JoinHandle<T>) {
+        let state = Arc::new(Mutex::new(JoinState {
+            output: None,
+            waker: None,
+        }));
+        (
+            JoinHandle {
+                state: state.clone(),
+            },
+            JoinHandle { state },
+        )
+    }
+
+    pub(crate) fn complete(&self, output: T) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.output = Some(output);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn try_take(&self) -> Option<T> {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .output
+            .take()
+    }
+
+    /// Returns `true` if the task has finished and its output was not taken yet.
+    pub fn is_finished(&self) -> bool {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .output
+            .is_some()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<T> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.output.take() {
+            Some(output) => Poll::Ready(output),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl UI {
+    /// Runs a future on the GUI thread. Unlike threads, the future does not need to be `Send`,
+    /// so it may hold and update controls. The future is first polled the next time the event
+    /// loop runs.
+    ///
+    /// Returns a [`JoinHandle`](struct.JoinHandle.html) which resolves to the output of the future,
+    /// or to `None` if it panicked. Like panics in callbacks, the panic is passed on to the
+    /// [panic handler](struct.UI.html#method.set_panic_handler) first.
+    ///
+    /// # Example
+    ///
+    /// ```no_run,edition2018
+    /// use libui::prelude::*;
+    /// use libui::controls::Label;
+    ///
+    /// # fn main() {
+    /// let ui = UI::init().unwrap();
+    /// let mut label = Label::new("Working...");
+    ///
+    /// let worker = ui.spawn_blocking(|| 6 * 7);
+    /// ui.spawn_local(async move {
+    ///     if let Ok(answer) = worker.await {
+    ///         label.set_text(&format!("The answer is {}", answer));
+    ///     }
+    /// });
+    /// ui.main();
+    /// # }
+    /// ```
+    pub fn spawn_local<F>(&self, future: F) -> JoinHandle<Option<F::Output>>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let (handle, completion) = JoinHandle::new();
+        let task = async_task(future, completion);
+
+        let id = NEXT_TASK_ID.with(|next| {
+            let id = next.get();
+            next.set(id.wrapping_add(1));
+            id
+        });
+        TASKS.with(|tasks| tasks.borrow_mut().insert(id, Box::pin(task)));
+
+        Waker::from(Arc::new(TaskWaker {
+            id,
+            remote: self.remote(),
+            scheduled: AtomicBool::new(false),
+        }))
+        .wake();
+
+        handle
+    }
+
+    /// Runs a function on a new background thread, returning a [`JoinHandle`](struct.JoinHandle.html)
+    /// which can be awaited on the GUI thread.
+    ///
+    /// The handle resolves to `Err` with the panic payload if the function panicked.
+    pub fn spawn_blocking<F, T>(&self, f: F) -> JoinHandle<thread::Result<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (handle, completion) = JoinHandle::new();
+        thread::spawn(move || {
+            completion.complete(panic::catch_unwind(panic::AssertUnwindSafe(f)));
+        });
+        handle
+    }
+
+    /// Runs the event loop until the given future completes, returning its output.
+    ///
+    /// This can be called from within callbacks to wait for a result without freezing
+    /// the user interface, as events keep being handled while waiting.
+    ///
+    /// Returns `None` if the future panicked, or if the UI quit before the future completed.
+    /// In the latter case the quit request is passed on, so the enclosing event loop stops as
+    /// well.
+    pub fn block_on<F>(&self, future: F) -> Option<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let handle = self.spawn_local(future);
+        let mut event_loop = self.event_loop();
+        loop {
+            if let Some(output) = handle.try_take() {
+                return output;
+            }
+            if !event_loop.next_event_tick() {
+                self.quit();
+                return None;
+            }
+        }
+    }
+}
+
+/// Wraps a future so that its output is handed to a `JoinHandle` once ready, or `None` once it
+/// panicked.
+fn async_task<F: Future>(
+    future: F,
+    completion: JoinHandle<Option<F::Output>>,
+) -> impl Future<Output = ()> {
+    struct Task<F: Future> {
+        future: Pin<Box<F>>,
+        completion: JoinHandle<Option<F::Output>>,
+    }
+
+    impl<F: Future> Future for Task<F> {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+            let future = &mut self.future;
+            match catch_panic(|| future.as_mut().poll(cx)) {
+                Some(Poll::Ready(output)) => {
+                    self.completion.complete(Some(output));
+                    Poll::Ready(())
+                }
+                Some(Poll::Pending) => Poll::Pending,
+                None => {
+                    self.completion.complete(None);
+                    Poll::Ready(())
+                }
+            }
+        }
+    }
+
+    Task {
+        future: Box::pin(future),
+        completion,
+    }
+}
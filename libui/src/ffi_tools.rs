@@ -1,8 +1,18 @@
 //! Utilities to manage the state of the interface to the libUI bindings.
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
 
 static INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+/// Identifier of the currently running libUI session, or `0` if there is none.
+/// Guarded by a mutex so other threads can't race the session ending.
+static SESSION: Mutex<usize> = Mutex::new(0);
+static LAST_SESSION: AtomicUsize = AtomicUsize::new(0);
+
+/// The thread which initialized libUI, and the only one allowed to use it.
+static GUI_THREAD: Mutex<Option<ThreadId>> = Mutex::new(None);
+
 /// Set the global flag stating that libUI is initialized.
 ///
 /// # Unsafety
@@ -14,6 +24,7 @@ static INITIALIZED: AtomicBool = AtomicBool::new(false);
 pub unsafe fn set_initialized() {
     assert!(!INITIALIZED.swap(true, Ordering::SeqCst),
         "Tried to initialize libUI when it was already initialized. Aborting because this is an unsafe situation.");
+    *GUI_THREAD.lock().unwrap_or_else(|e| e.into_inner()) = Some(thread::current().id());
 }
 
 /// Set the global flag stating that libUI is no longer initialized.
@@ -23,6 +34,7 @@ pub unsafe fn set_initialized() {
 /// the program could try to create a new instance, violating the library's
 /// invariants and likely causing a segfault.
 pub unsafe fn unset_initialized() {
+    *GUI_THREAD.lock().unwrap_or_else(|e| e.into_inner()) = None;
     INITIALIZED.store(false, Ordering::SeqCst);
 }
 
@@ -30,3 +42,57 @@ pub unsafe fn unset_initialized() {
 pub fn is_initialized() -> bool {
     INITIALIZED.load(Ordering::SeqCst)
 }
+
+/// In debug builds, asserts that the API named `api` is used on the thread which initialized
+/// libUI. Does nothing in release builds, or while libUI is not initialized.
+///
+/// # Panics
+/// Panics, naming the API, if called from any other thread.
+#[inline]
+pub fn debug_assert_gui_thread(api: &str) {
+    if cfg!(debug_assertions) {
+        let gui_thread = *GUI_THREAD.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(gui_thread) = gui_thread {
+            let current = thread::current();
+            assert!(
+                current.id() == gui_thread,
+                "`{}` was called on thread {:?} ({:?}), but libUI may only be used on the thread which called `UI::init`",
+                api,
+                current.name().unwrap_or("<unnamed>"),
+                current.id()
+            );
+        }
+    }
+}
+
+/// Starts a new libUI session and returns its identifier.
+///
+/// Session identifiers are never reused, so handles tied to a previous session
+/// can tell that the library was uninitialized in the meantime.
+pub fn begin_session() -> usize {
+    let id = LAST_SESSION.fetch_add(1, Ordering::SeqCst) + 1;
+    *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = id;
+    id
+}
+
+/// Ends the current libUI session. Blocks until no other thread is inside
+/// [`with_session`](fn.with_session.html).
+pub fn end_session() {
+    *SESSION.lock().unwrap_or_else(|e| e.into_inner()) = 0;
+}
+
+/// Returns the identifier of the current libUI session, or `0` if there is none.
+pub fn current_session() -> usize {
+    *SESSION.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Runs `f` if `session` is still the current libUI session, keeping the session
+/// alive until `f` returns. Returns `None` if the session has already ended.
+pub fn with_session<R, F: FnOnce() -> R>(session: usize, f: F) -> Option<R> {
+    let current = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    if session != 0 && *current == session {
+        Some(f())
+    } else {
+        None
+    }
+}
@@ -38,7 +38,11 @@
 extern crate bitflags;
 
 extern crate libc;
+#[cfg(feature = "derive")]
+extern crate libui_derive;
 extern crate libui_ffi;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 
 mod builder;
 mod callback_helpers;
@@ -46,18 +50,28 @@ mod compile_tests;
 pub mod controls;
 pub mod draw;
 mod error;
+mod executor;
 mod ffi_tools;
 pub mod menus;
+mod ownership;
+mod panics;
 pub mod str_tools;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timer;
+mod trace;
 mod ui;
 
 pub use error::UIError;
-pub use ui::{EventLoop, UI};
+pub use executor::JoinHandle;
+pub use panics::CallbackPanic;
+pub use timer::Timer;
+pub use ui::{EventLoop, QuitAction, RemoteUI, UI};
 
 /// Common imports are packaged into this module. It's meant to be glob-imported: `use libui::prelude::*`.
 pub mod prelude {
     pub use controls::LayoutStrategy;
     pub use controls::{NumericEntry, TextEntry};
-    pub use controls::{Window, WindowType};
-    pub use ui::UI;
+    pub use controls::{CloseAction, Window, WindowType};
+    pub use ui::{QuitAction, UI};
 }
@@ -1,7 +1,8 @@
 //! Menus that appear at the top of windows, and the items that go in them.
 
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{from_void_ptr, invoke, register_callback};
 use controls::Window;
+use ffi_tools;
 use std::ffi::CString;
 use std::os::raw::{c_int, c_void};
 use libui_ffi::{self, uiMenu, uiMenuItem, uiWindow};
@@ -10,7 +11,7 @@ use libui_ffi::{self, uiMenu, uiMenuItem, uiWindow};
 /// the text on `MenuItem`s cannot be changed after creation.
 #[derive(Clone)]
 pub struct MenuItem {
-    ui_menu_item: *mut uiMenuItem,
+    pub(crate) ui_menu_item: *mut uiMenuItem,
 }
 
 /// A `Menu` represents one of the top-level menus at the top of a window. As that bar is unique
@@ -23,17 +24,20 @@ pub struct Menu {
 impl MenuItem {
     /// Enables the item, allowing it to be selected. This is the default state of a menu item.
     pub fn enable(&self) {
+        ffi_tools::debug_assert_gui_thread("MenuItem::enable");
         unsafe { libui_ffi::uiMenuItemEnable(self.ui_menu_item) }
     }
 
     /// Disables the item, preventing it from being selected and providing a visual cue to the
     /// user that it cannot be selected.
     pub fn disable(&self) {
+        ffi_tools::debug_assert_gui_thread("MenuItem::disable");
         unsafe { libui_ffi::uiMenuItemDisable(self.ui_menu_item) }
     }
 
     /// Returns `true` if the menu item is checked, and false if it is not checked (or not checkable).
     pub fn checked(&self) -> bool {
+        ffi_tools::debug_assert_gui_thread("MenuItem::checked");
         unsafe { libui_ffi::uiMenuItemChecked(self.ui_menu_item) != 0 }
     }
 
@@ -41,6 +45,7 @@ impl MenuItem {
     ///
     /// Setting the checked value of a non-checkable menu item has no effect.
     pub fn set_checked(&self, checked: bool) {
+        ffi_tools::debug_assert_gui_thread("MenuItem::set_checked");
         unsafe { libui_ffi::uiMenuItemSetChecked(self.ui_menu_item, checked as c_int) }
     }
 
@@ -57,14 +62,20 @@ impl MenuItem {
             let menu_item = unsafe { MenuItem::from_raw(menu_item) };
             let window = unsafe { Window::from_raw(window) };
             unsafe {
-                from_void_ptr::<G>(data)(&menu_item, &window);
+                invoke("MenuItem", "clicked", || from_void_ptr::<G>(data)(&menu_item, &window));
             }
         }
+        ffi_tools::debug_assert_gui_thread("MenuItem::on_clicked");
         unsafe {
             libui_ffi::uiMenuItemOnClicked(
                 self.ui_menu_item,
                 Some(c_callback::<F>),
-                to_heap_ptr(callback),
+                register_callback(
+                    self.ui_menu_item,
+                    "clicked",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
             );
         }
     }
@@ -78,6 +89,7 @@ impl MenuItem {
 impl Menu {
     /// Creates a new menu with the given name to be displayed in the menubar at the top of the window.
     pub fn new(name: &str) -> Menu {
+        ffi_tools::debug_assert_gui_thread("Menu::new");
         unsafe {
             let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
             Menu {
@@ -88,6 +100,7 @@ impl Menu {
 
     /// Adds a new item with the given name to the menu.
     pub fn append_item(&self, name: &str) -> MenuItem {
+        ffi_tools::debug_assert_gui_thread("Menu::append_item");
         unsafe {
             let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
             MenuItem {
@@ -98,6 +111,7 @@ impl Menu {
 
     /// Adds a new togglable (checkbox) item with the given name to the menu.
     pub fn append_check_item(&self, name: &str) -> MenuItem {
+        ffi_tools::debug_assert_gui_thread("Menu::append_check_item");
         unsafe {
             let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
             MenuItem {
@@ -108,6 +122,7 @@ impl Menu {
 
     /// Adds a seperator to the menu.
     pub fn append_separator(&self) {
+        ffi_tools::debug_assert_gui_thread("Menu::append_separator");
         unsafe { libui_ffi::uiMenuAppendSeparator(self.ui_menu) }
     }
 
@@ -116,6 +131,7 @@ impl Menu {
     /// Depending on you platform, this can be a special system item.
     /// Warning: Only one such menu item may exist per application.
     pub fn append_about_item(&self) -> MenuItem {
+        ffi_tools::debug_assert_gui_thread("Menu::append_about_item");
         unsafe {
             MenuItem {
                 ui_menu_item: libui_ffi::uiMenuAppendAboutItem(self.ui_menu),
@@ -130,6 +146,7 @@ impl Menu {
     /// to register the callback.
     /// Warning: Only one such menu item may exist per application.
     pub fn append_quit_item(&self) -> MenuItem {
+        ffi_tools::debug_assert_gui_thread("Menu::append_quit_item");
         unsafe {
             MenuItem {
                 ui_menu_item: libui_ffi::uiMenuAppendQuitItem(self.ui_menu),
@@ -142,6 +159,7 @@ impl Menu {
     /// Depending on you platform, this can be a special system item.
     /// Warning: Only one such menu item may exist per application.
     pub fn append_preferences_item(&self) -> MenuItem {
+        ffi_tools::debug_assert_gui_thread("Menu::append_preferences_item");
         unsafe {
             MenuItem {
                 ui_menu_item: libui_ffi::uiMenuAppendPreferencesItem(self.ui_menu),
@@ -0,0 +1,386 @@
+//! Tracks who owns each control: its Rust handles or its parent.
+//!
+//! libui does not reference count controls, so every control handle created by this crate is
+//! registered here, together with the control's parent and children. A control without a parent
+//! is destroyed once its last handle is dropped, while a control with a parent is destroyed
+//! along with that parent.
+//!
+//! Handles to a control destroyed along with its parent outlive it. As libui may reuse its
+//! address for a new control, each entry has a generation, which handles remember. Handles of
+//! an earlier generation are stale and no longer counted.
+
+use callback_helpers::unregister_callbacks;
+use ffi_tools;
+use libui_ffi::{self, uiControl};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::mem;
+use std::os::raw::c_int;
+
+/// Removes the child at the given index from a container.
+pub type RemoveChild = unsafe fn(parent: *mut uiControl, index: c_int);
+
+/// Tells apart the controls registered at the same address over time.
+pub type Generation = u64;
+
+struct Entry {
+    generation: Generation,
+    kind: Option<&'static str>,
+    handles: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    remove_child: Option<RemoveChild>,
+    // Data libui uses for as long as the control exists, such as handlers and models.
+    kept_alive: Vec<Box<dyn Any>>,
+}
+
+impl Entry {
+    fn new() -> Entry {
+        Entry {
+            generation: NEXT_GENERATION.with(|next| next.replace(next.get() + 1)),
+            kind: None,
+            handles: 0,
+            parent: None,
+            children: Vec::new(),
+            remove_child: None,
+            kept_alive: Vec::new(),
+        }
+    }
+}
+
+thread_local! {
+    static CONTROLS: RefCell<HashMap<usize, Entry>> = RefCell::new(HashMap::new());
+    // Not reset when uninitializing, so handles left from before are stale afterwards.
+    static NEXT_GENERATION: Cell<Generation> = const { Cell::new(1) };
+}
+
+/// Registers a new Rust handle to the control, returning the generation the handle belongs to.
+pub fn retain(control: *mut uiControl) -> Generation {
+    if control.is_null() {
+        return 0;
+    }
+    CONTROLS.with(|controls| {
+        let mut controls = controls.borrow_mut();
+        let entry = controls.entry(control as usize).or_insert_with(Entry::new);
+        entry.handles += 1;
+        entry.generation
+    })
+}
+
+/// Registers a copy of a handle of the given generation, unless the handle is stale.
+pub fn retain_handle(control: *mut uiControl, generation: Generation) {
+    CONTROLS.with(|controls| match controls.borrow_mut().get_mut(&(control as usize)) {
+        Some(entry) if entry.generation == generation => entry.handles += 1,
+        _ => {}
+    });
+}
+
+/// Returns `true` if a handle of the given generation refers to a control which was not
+/// destroyed yet.
+pub fn is_current(control: *mut uiControl, generation: Generation) -> bool {
+    CONTROLS.with(|controls| {
+        controls
+            .borrow()
+            .get(&(control as usize))
+            .is_some_and(|entry| entry.generation == generation)
+    })
+}
+
+/// Records the type of the control, unless it is known already.
+pub fn set_kind(control: *mut uiControl, kind: &'static str) {
+    if control.is_null() {
+        return;
+    }
+    CONTROLS.with(|controls| {
+        let mut controls = controls.borrow_mut();
+        let entry = controls.entry(control as usize).or_insert_with(Entry::new);
+        entry.kind = entry.kind.or(Some(kind));
+    });
+}
+
+/// Keeps `value` alive until the control is destroyed, for data which libui uses as long as the
+/// control exists. It is dropped only after libui destroyed the control and its children.
+pub fn keep_alive(control: *mut uiControl, value: Box<dyn Any>) {
+    CONTROLS.with(|controls| {
+        controls
+            .borrow_mut()
+            .entry(control as usize)
+            .or_insert_with(Entry::new)
+            .kept_alive
+            .push(value);
+    });
+}
+
+/// Returns a clone of the first value of type `T` kept alive for the control, if any.
+pub fn kept_alive<T: Any + Clone>(control: *mut uiControl) -> Option<T> {
+    CONTROLS.with(|controls| {
+        controls.borrow().get(&(control as usize)).and_then(|entry| {
+            entry
+                .kept_alive
+                .iter()
+                .find_map(|value| value.downcast_ref::<T>())
+                .cloned()
+        })
+    })
+}
+
+/// Returns the type of the control, if it is known.
+pub fn kind(control: *mut uiControl) -> Option<&'static str> {
+    CONTROLS.with(|controls| {
+        controls
+            .borrow()
+            .get(&(control as usize))
+            .and_then(|entry| entry.kind)
+    })
+}
+
+/// Drops a Rust handle of the given generation to the control, destroying it if it was the last
+/// handle to a control without a parent. Does nothing if the handle is stale.
+pub fn release(control: *mut uiControl, generation: Generation) {
+    // Everything is destroyed at once when uninitializing, handles dropped later are dangling.
+    if control.is_null() || !ffi_tools::is_initialized() {
+        return;
+    }
+    let orphaned = CONTROLS.with(|controls| match controls.borrow_mut().get_mut(&(control as usize)) {
+        Some(entry) if entry.generation == generation => {
+            entry.handles = entry.handles.saturating_sub(1);
+            entry.handles == 0 && entry.parent.is_none()
+        }
+        _ => false,
+    });
+    // Controls attached outside of this crate are owned by their parent as well.
+    if orphaned && unsafe { libui_ffi::uiControlParent(control).is_null() } {
+        destroy(control);
+    }
+}
+
+/// Records that `child` was inserted into `parent` at the given index, or appended if `None`.
+///
+/// `remove_child` is used to remove the child from the parent again, if the parent supports it.
+pub fn adopt(
+    parent: *mut uiControl,
+    child: *mut uiControl,
+    index: Option<usize>,
+    remove_child: Option<RemoveChild>,
+) {
+    CONTROLS.with(|controls| {
+        let mut controls = controls.borrow_mut();
+        controls
+            .entry(child as usize)
+            .or_insert_with(Entry::new)
+            .parent = Some(parent as usize);
+
+        let parent = controls.entry(parent as usize).or_insert_with(Entry::new);
+        parent.remove_child = remove_child;
+        match index {
+            Some(index) if index < parent.children.len() => {
+                parent.children.insert(index, child as usize)
+            }
+            _ => parent.children.push(child as usize),
+        }
+    });
+}
+
+/// Records that the child at the given index was removed from `parent`, destroying it if no
+/// Rust handles to it are left.
+pub fn disown(parent: *mut uiControl, index: usize) {
+    let child = CONTROLS.with(|controls| {
+        let mut controls = controls.borrow_mut();
+        let child = match controls.get_mut(&(parent as usize)) {
+            Some(parent) if index < parent.children.len() => parent.children.remove(index),
+            _ => return None,
+        };
+        let entry = controls.get_mut(&child)?;
+        entry.parent = None;
+        Some((child, entry.handles))
+    });
+    if let Some((child, 0)) = child {
+        destroy(child as *mut uiControl);
+    }
+}
+
+/// Records that all children were removed from `parent`, destroying those without Rust handles.
+pub fn disown_all(parent: *mut uiControl) {
+    let count = CONTROLS.with(|controls| {
+        controls
+            .borrow()
+            .get(&(parent as usize))
+            .map_or(0, |parent| parent.children.len())
+    });
+    for index in (0..count).rev() {
+        disown(parent, index);
+    }
+}
+
+/// Removes the control from its parent, if it has one.
+///
+/// Returns `false` if the parent does not support removing children.
+pub fn remove_from_parent(control: *mut uiControl) -> bool {
+    let removal = CONTROLS.with(|controls| {
+        let controls = controls.borrow();
+        let parent = controls.get(&(control as usize))?.parent?;
+        let entry = controls.get(&parent)?;
+        let index = entry.children.iter().position(|&child| child == control as usize)?;
+        Some((parent, index, entry.remove_child))
+    });
+    match removal {
+        None => true,
+        Some((_, _, None)) => false,
+        Some((parent, index, Some(remove_child))) => {
+            unsafe { remove_child(parent as *mut uiControl, index as c_int) };
+            disown(parent as *mut uiControl, index);
+            true
+        }
+    }
+}
+
+/// Unregisters the callbacks of the control and all of its descendants.
+pub fn unregister_tree_callbacks(control: *mut uiControl) {
+    for control in tree(control) {
+        unregister_callbacks(control as *mut uiControl);
+    }
+}
+
+/// Destroys the control and all of its descendants.
+pub fn destroy(control: *mut uiControl) {
+    unsafe { libui_ffi::uiControlDestroy(control) };
+    forget(control);
+}
+
+/// Forgets the control and all of its descendants after libui destroyed them, dropping their
+/// callbacks and the data kept alive for them.
+fn forget(control: *mut uiControl) {
+    let entries = unregister_tree(control);
+    // Dropped outside of the borrow, as callbacks may hold handles to other controls.
+    drop(entries);
+}
+
+/// Forgets the control and all of its descendants, which libui may still use. Their callbacks
+/// are dropped, but the data kept alive for them is leaked.
+fn abandon(control: *mut uiControl) {
+    for entry in unregister_tree(control) {
+        mem::forget(entry.kept_alive);
+    }
+}
+
+/// Removes the control and all of its descendants from the registry, unregistering their
+/// callbacks, and returns their entries.
+fn unregister_tree(control: *mut uiControl) -> Vec<Entry> {
+    let controls = tree(control);
+    let entries: Vec<Entry> = CONTROLS.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let parent = registry.get(&(control as usize)).and_then(|entry| entry.parent);
+        if let Some(parent) = parent.and_then(|parent| registry.get_mut(&parent)) {
+            parent.children.retain(|&child| child != control as usize);
+        }
+        controls
+            .iter()
+            .filter_map(|control| registry.remove(control))
+            .collect()
+    });
+    for control in controls {
+        unregister_callbacks(control as *mut uiControl);
+    }
+    entries
+}
+
+/// Destroys all controls without a parent. Used when uninitializing.
+pub fn destroy_all() {
+    let roots: Vec<usize> = CONTROLS.with(|controls| {
+        controls
+            .borrow()
+            .iter()
+            .filter(|&(_, entry)| entry.parent.is_none())
+            .map(|(&control, _)| control)
+            .collect()
+    });
+    for control in roots {
+        // Destroying a control drops its callbacks, which may have destroyed other roots already.
+        if !CONTROLS.with(|controls| controls.borrow().contains_key(&control)) {
+            continue;
+        }
+        let control = control as *mut uiControl;
+        if unsafe { libui_ffi::uiControlParent(control).is_null() } {
+            destroy(control);
+        } else {
+            abandon(control);
+        }
+    }
+    // Every entry belongs to a tree with a root, but make sure nothing survives into the next
+    // initialization.
+    let leftovers = CONTROLS.with(|controls| mem::take(&mut *controls.borrow_mut()));
+    drop(leftovers);
+}
+
+/// Returns the control followed by all of its descendants.
+pub fn tree(control: *mut uiControl) -> Vec<usize> {
+    CONTROLS.with(|controls| {
+        let controls = controls.borrow();
+        let mut tree = vec![control as usize];
+        let mut next = 0;
+        while next < tree.len() {
+            if let Some(entry) = controls.get(&tree[next]) {
+                tree.extend_from_slice(&entry.children);
+            }
+            next += 1;
+        }
+        tree
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn children_are_tracked_until_disowned() {
+        let parent = 0x100 as *mut uiControl;
+        let first = 0x200 as *mut uiControl;
+        let second = 0x300 as *mut uiControl;
+        retain(first);
+        retain(second);
+
+        adopt(parent, first, None, None);
+        adopt(parent, second, Some(0), None);
+        assert_eq!(tree(parent), vec![0x100, 0x300, 0x200]);
+
+        // Both children still have a handle, so they are not destroyed.
+        disown(parent, 0);
+        assert_eq!(tree(parent), vec![0x100, 0x200]);
+
+        forget(parent);
+        assert!(CONTROLS.with(|controls| !controls.borrow().contains_key(&0x200)));
+        assert!(CONTROLS.with(|controls| controls.borrow().contains_key(&0x300)));
+    }
+
+    #[test]
+    fn handles_to_destroyed_controls_are_stale() {
+        let control = 0x500 as *mut uiControl;
+        let stale = retain(control);
+        forget(control);
+        assert!(!is_current(control, stale));
+
+        // A new control at the same address is not counted by the stale handle.
+        let current = retain(control);
+        assert_ne!(current, stale);
+        retain_handle(control, stale);
+        assert_eq!(
+            CONTROLS.with(|controls| controls.borrow()[&0x500].handles),
+            1
+        );
+        assert!(is_current(control, current));
+        forget(control);
+    }
+
+    #[test]
+    fn kind_is_recorded_once() {
+        let control = 0x400 as *mut uiControl;
+        set_kind(control, "HorizontalBox");
+        set_kind(control, "Spacer");
+        assert_eq!(kind(control), Some("HorizontalBox"));
+
+        forget(control);
+        assert_eq!(kind(control), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Keeps panics in callbacks from unwinding into libui.
+//!
+//! Unwinding out of an `extern "C"` function aborts the process, so every callback called by
+//! libui runs inside `catch_panic`. Caught panics are passed on to the handler set with
+//! `UI::set_panic_handler`, which by default shows them in an error dialog and quits.
+
+use controls::Window;
+use libui_ffi;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use ui::UI;
+
+/// A panic caught in a callback, as passed to the handler set with
+/// [`UI::set_panic_handler`](struct.UI.html#method.set_panic_handler).
+#[derive(Debug)]
+pub struct CallbackPanic {
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+}
+
+impl CallbackPanic {
+    /// The message the callback panicked with.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The source location of the panic, if known.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// The backtrace of the panic, or an empty string if it could not be captured.
+    pub fn backtrace(&self) -> &str {
+        &self.backtrace
+    }
+}
+
+impl fmt::Display for CallbackPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some(ref location) => write!(f, "callback panicked at {}: {}", location, self.message)?,
+            None => write!(f, "callback panicked: {}", self.message)?,
+        }
+        if !self.backtrace.is_empty() {
+            write!(f, "\n\n{}", self.backtrace)?;
+        }
+        Ok(())
+    }
+}
+
+type PanicHandler = Box<dyn FnMut(&CallbackPanic)>;
+
+thread_local! {
+    // How many callbacks are currently running inside `catch_panic`.
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+    // The last panic recorded by the panic hook, waiting to be handled.
+    static LAST_PANIC: RefCell<Option<CallbackPanic>> = const { RefCell::new(None) };
+    static HANDLER: RefCell<Option<PanicHandler>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+/// Installs a panic hook which records the backtrace of panics in callbacks. Other panics are
+/// left to the previous hook alone.
+pub fn install_hook() {
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let catching = CATCHING.try_with(|catching| catching.get() > 0).unwrap_or(false);
+            if catching {
+                let panic = CallbackPanic {
+                    message: payload_message(info.payload()),
+                    location: info.location().map(|location| location.to_string()),
+                    backtrace: Backtrace::force_capture().to_string(),
+                };
+                let _ = LAST_PANIC.try_with(|last| *last.borrow_mut() = Some(panic));
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Runs `f`, which calls into user code from a libui callback. Returns `None` if it panicked,
+/// after passing the panic on to the panic handler.
+pub fn catch_panic<R, F: FnOnce() -> R>(f: F) -> Option<R> {
+    CATCHING.with(|catching| catching.set(catching.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(catching.get() - 1));
+
+    match result {
+        Ok(value) => Some(value),
+        Err(payload) => {
+            let panic = LAST_PANIC
+                .with(|last| last.borrow_mut().take())
+                .unwrap_or_else(|| CallbackPanic {
+                    message: payload_message(&*payload),
+                    location: None,
+                    backtrace: String::new(),
+                });
+            handle(&panic);
+            None
+        }
+    }
+}
+
+/// Drops the panic handler. Used when uninitializing.
+pub fn clear_handler() {
+    let handler = HANDLER.with(|handler| handler.borrow_mut().take());
+    drop(handler);
+}
+
+fn handle(panic: &CallbackPanic) {
+    // The handler is taken out while it runs, so it may set a new handler.
+    let handler = HANDLER.with(|handler| handler.borrow_mut().take());
+    let result = panic::catch_unwind(AssertUnwindSafe(|| match handler {
+        Some(mut handler) => {
+            handler(panic);
+            HANDLER.with(|current| {
+                let mut current = current.borrow_mut();
+                if current.is_none() {
+                    *current = Some(handler);
+                }
+            });
+        }
+        None => default_handler(panic),
+    }));
+    // A panicking handler can not be reported anywhere, but must not unwind into libui either.
+    drop(result);
+}
+
+fn default_handler(panic: &CallbackPanic) {
+    if let Some(window) = Window::first() {
+        window.modal_err("Unexpected error", &panic.to_string());
+    }
+    unsafe { libui_ffi::uiQuit() }
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Box<dyn Any>")
+    }
+}
+
+impl UI {
+    /// Sets the function which handles panics in callbacks, replacing the previous one.
+    ///
+    /// Callbacks are called from C code, which a panic must not unwind into. Instead, panics
+    /// are caught and passed on to this handler, after which the event loop continues.
+    /// By default, the panic message and backtrace are shown in an error dialog on the first
+    /// open window, after which the application quits.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libui::prelude::*;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// ui.set_panic_handler(|panic| eprintln!("{}", panic));
+    /// ```
+    pub fn set_panic_handler<F: FnMut(&CallbackPanic) + 'static>(&self, handler: F) {
+        let previous = HANDLER.with(|current| current.borrow_mut().replace(Box::new(handler)));
+        drop(previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn panics_are_passed_to_the_handler() {
+        install_hook();
+        let messages = Rc::new(RefCell::new(Vec::new()));
+        let captured = messages.clone();
+        HANDLER.with(|handler| {
+            *handler.borrow_mut() = Some(Box::new(move |panic: &CallbackPanic| {
+                captured.borrow_mut().push(panic.message().to_string())
+            }))
+        });
+
+        assert_eq!(catch_panic(|| 42), Some(42));
+        assert_eq!(catch_panic(|| -> i32 { panic!("boom") }), None);
+        assert_eq!(*messages.borrow(), vec![String::from("boom")]);
+
+        clear_handler();
+    }
+}
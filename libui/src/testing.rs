@@ -0,0 +1,331 @@
+//! Drives real controls from tests, as if a user interacted with them.
+//!
+//! libui may only be initialized once, and only used from the thread which initialized it,
+//! while `cargo test` runs tests in parallel on many threads. Therefore, [`run`](fn.run.html)
+//! hands each test to a dedicated GUI thread, which initializes the UI on first use. On Linux,
+//! a virtual X display is started with `Xvfb` for the tests, unless a display is available.
+//!
+//! Within a test, the functions of this module fire the callbacks registered on controls the
+//! same way libui does when the user acts, after updating the state of the control accordingly.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use libui::controls::{Button, Label};
+//! use libui::testing;
+//!
+//! testing::run(|_ui| {
+//!     let mut label = Label::new("Not clicked");
+//!     let mut button = Button::new("Click me");
+//!     button.on_clicked({
+//!         let mut label = label.clone();
+//!         move |_| label.set_text("Clicked")
+//!     });
+//!
+//!     testing::click(&button);
+//!     assert_eq!(label.text(), "Clicked");
+//! });
+//! ```
+
+use callback_helpers::registered_callback;
+use controls::{
+    Button, Checkbox, ColorButton, Combobox, Control, RadioButtons, Slider, Spinbox, TabGroup, Table,
+    TextEntry, Window,
+};
+use libui_ffi;
+use menus::MenuItem;
+use ownership;
+use std::os::raw::{c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, Once, OnceLock};
+use std::thread;
+use ui::UI;
+
+type Job = Box<dyn FnOnce(&UI) + Send>;
+
+static GUI_THREAD: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+
+/// Runs `test` on the GUI thread, returning its result once it finished. A panic in the test,
+/// such as a failed assertion, is passed on to the caller.
+///
+/// Tests are run one after another. Functions queued on the event loop by a test are run
+/// before `run` returns, after which the windows the test left open are destroyed.
+pub fn run<F, R>(test: F) -> R
+where
+    F: FnOnce(&UI) -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let jobs = GUI_THREAD
+        .get_or_init(|| Mutex::new(spawn_gui_thread()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+
+    let (result_sender, result) = mpsc::channel();
+    jobs.send(Box::new(move |ui: &UI| {
+        // Holding on to these keeps their addresses from being reused by new windows.
+        let windows = Window::all();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let result = test(ui);
+            ui.event_loop().pump_pending();
+            result
+        }));
+        // Destroyed even if the test failed, so the next test starts out the same.
+        for window in Window::all() {
+            if !windows.iter().any(|before| before.ptr() == window.ptr()) {
+                window.destroy();
+            }
+        }
+        let _ = result_sender.send(result);
+    }))
+    .expect("the GUI thread has stopped");
+
+    match result.recv().expect("the GUI thread has stopped") {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+fn spawn_gui_thread() -> Sender<Job> {
+    let (jobs, receiver) = mpsc::channel::<Job>();
+    thread::Builder::new()
+        .name(String::from("libui-testing"))
+        .spawn(move || {
+            ensure_display();
+            let ui = UI::init().expect("failed to initialize libui");
+            for job in receiver {
+                job(&ui);
+            }
+        })
+        .expect("failed to spawn the GUI thread");
+    jobs
+}
+
+/// Makes sure `UI::init` has a display to connect to, by starting a virtual X display on Linux
+/// if there is none. [`run`](fn.run.html) does this by itself, so this is only needed by tests
+/// which initialize the UI themselves.
+pub fn ensure_display() {
+    static DISPLAY: Once = Once::new();
+    DISPLAY.call_once(start_display);
+}
+
+/// Starts a virtual X display if there is no display to connect to.
+///
+/// `DISPLAY` can not be set here, as other threads are already running. Instead, the display is
+/// opened before libui initializes GTK, which then uses it as the default display.
+#[cfg(all(unix, not(target_os = "macos"), not(feature = "mock")))]
+fn start_display() {
+    use std::env;
+    use std::ffi::CString;
+    use std::os::raw::c_char;
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    extern "C" {
+        fn gdk_set_allowed_backends(backends: *const c_char);
+        fn gdk_display_open(display_name: *const c_char) -> *mut c_void;
+        fn gdk_display_manager_get() -> *mut c_void;
+        fn gdk_display_manager_set_default_display(manager: *mut c_void, display: *mut c_void);
+    }
+
+    if env::var_os("DISPLAY").is_some() || env::var_os("WAYLAND_DISPLAY").is_some() {
+        return;
+    }
+
+    // Skip the displays in use, as `xvfb-run --auto-servernum` does.
+    let number = (99..)
+        .find(|number| !Path::new(&format!("/tmp/.X{}-lock", number)).exists())
+        .unwrap();
+    let display = format!(":{}", number);
+    // With `-terminate`, the server exits once the test process disconnects. It is never waited
+    // for, as it outlives the GUI thread.
+    let server = Command::new("Xvfb")
+        .args([
+            &display,
+            "-screen",
+            "0",
+            "1280x1024x24",
+            "-nolisten",
+            "tcp",
+            "-terminate",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("no display is available and Xvfb could not be started");
+    std::mem::forget(server);
+
+    let socket = format!("/tmp/.X11-unix/X{}", number);
+    let started = Instant::now();
+    while !Path::new(&socket).exists() {
+        if started.elapsed() > Duration::from_secs(10) {
+            panic!("Xvfb did not start within 10 seconds");
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let name = CString::new(display.as_str()).unwrap();
+    unsafe {
+        gdk_set_allowed_backends(b"x11\0".as_ptr() as *const c_char);
+        let gdk_display = gdk_display_open(name.as_ptr());
+        if gdk_display.is_null() {
+            panic!("failed to connect to the virtual display {}", display);
+        }
+        gdk_display_manager_set_default_display(gdk_display_manager_get(), gdk_display);
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"), not(feature = "mock"))))]
+fn start_display() {}
+
+/// Calls the callback registered for `event` on the control at `owner`, which takes no
+/// arguments besides the control. Returns `false` if no callback is registered.
+fn fire<T>(owner: *mut T, event: &'static str) -> bool {
+    match registered_callback(owner, event) {
+        Some((trampoline, data)) => {
+            let trampoline: extern "C" fn(*mut T, *mut c_void) =
+                unsafe { std::mem::transmute(trampoline) };
+            trampoline(owner, data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Calls the callback registered for `event` on the control at `owner`, which takes an
+/// additional integer. Returns `false` if no callback is registered.
+fn fire_with_int<T>(owner: *mut T, event: &'static str, value: c_int) -> bool {
+    match registered_callback(owner, event) {
+        Some((trampoline, data)) => {
+            let trampoline: extern "C" fn(*mut T, c_int, *mut c_void) =
+                unsafe { std::mem::transmute(trampoline) };
+            trampoline(owner, value, data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Clicks the button, running its `on_clicked` callback.
+pub fn click(button: &Button) -> bool {
+    fire(button.ptr(), "clicked")
+}
+
+/// Toggles the checkbox, running its `on_toggled` callback.
+pub fn toggle(checkbox: &mut Checkbox) -> bool {
+    let checked = checkbox.checked();
+    checkbox.set_checked(!checked);
+    fire(checkbox.ptr(), "toggled")
+}
+
+/// Replaces the text of the entry, running its `on_changed` callback.
+pub fn type_text<E>(entry: &mut E, text: &str) -> bool
+where
+    E: TextEntry + Clone + Into<Control>,
+{
+    entry.set_value(text);
+    let control: Control = entry.clone().into();
+    fire(control.as_ui_control(), "changed")
+}
+
+/// Picks a color with the color button, running its `on_changed` callback.
+pub fn pick_color(button: &mut ColorButton, r: f64, g: f64, b: f64, a: f64) -> bool {
+    button.set_color(r, g, b, a);
+    fire(button.ptr(), "changed")
+}
+
+/// Sets the value of the spinbox, running its `on_changed` callback.
+pub fn set_spinbox_value(spinbox: &mut Spinbox, value: i32) -> bool {
+    unsafe { libui_ffi::uiSpinboxSetValue(spinbox.ptr(), value) };
+    fire(spinbox.ptr(), "changed")
+}
+
+/// Moves the slider to the value, running its `on_changed` callback.
+pub fn set_slider_value(slider: &mut Slider, value: i32) -> bool {
+    unsafe { libui_ffi::uiSliderSetValue(slider.ptr(), value) };
+    fire(slider.ptr(), "changed")
+}
+
+/// Releases the slider at its current value, running its `on_released` callback.
+pub fn release_slider(slider: &Slider) -> bool {
+    fire(slider.ptr(), "released")
+}
+
+/// Selects the item at the index, running the combobox's `on_selected` callback.
+pub fn select_item(combobox: &mut Combobox, index: i32) -> bool {
+    combobox.set_selected(index);
+    fire(combobox.ptr(), "selected")
+}
+
+/// Switches to the tab at the index, running the tab group's `on_selected` callback.
+pub fn select_tab(tab_group: &mut TabGroup, index: usize) -> bool {
+    tab_group.set_selected(index).is_ok() && fire(tab_group.ptr(), "selected")
+}
+
+/// Selects the radio button at the index, running the `on_selected` callback.
+pub fn select_radio_button(radio_buttons: &mut RadioButtons, index: i32) -> bool {
+    radio_buttons.set_selected(index);
+    fire(radio_buttons.ptr(), "selected")
+}
+
+/// Clicks a row of the table, selecting it and running the table's `on_row_clicked` and
+/// `on_selection_changed` callbacks.
+pub fn select_row(table: &mut Table, row: i32) -> bool {
+    table.set_selection(&vec![row]);
+    let clicked = fire_with_int(table.ptr(), "row_clicked", row);
+    fire(table.ptr(), "selection_changed") || clicked
+}
+
+/// Double clicks a row of the table, running its `on_row_double_clicked` callback.
+pub fn double_click_row(table: &mut Table, row: i32) -> bool {
+    fire_with_int(table.ptr(), "row_double_clicked", row)
+}
+
+/// Clicks a column header of the table, running its `on_header_clicked` callback.
+pub fn click_header(table: &mut Table, column: i32) -> bool {
+    fire_with_int(table.ptr(), "header_clicked", column)
+}
+
+/// Clicks the menu item while the window is active, running its `on_clicked` callback.
+pub fn click_menu_item(item: &MenuItem, window: &Window) -> bool {
+    match registered_callback(item.ui_menu_item, "clicked") {
+        Some((trampoline, data)) => {
+            let trampoline: extern "C" fn(
+                *mut libui_ffi::uiMenuItem,
+                *mut libui_ffi::uiWindow,
+                *mut c_void,
+            ) = unsafe { std::mem::transmute(trampoline) };
+            trampoline(item.ui_menu_item, window.ptr(), data);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Resizes the content area of the window, running its `on_content_size_changed` callback.
+pub fn resize_window(window: &mut Window, width: i32, height: i32) -> bool {
+    window.set_content_size(width, height);
+    fire(window.ptr(), "content_size_changed")
+}
+
+/// Returns the button labelled `text` within the window, such as one of the buttons of a
+/// question or a [`Dialog`](../controls/struct.Dialog.html), or `None` if there is none.
+pub fn find_button(window: &Window, text: &str) -> Option<Button> {
+    ownership::tree(window.ptr() as *mut libui_ffi::uiControl)
+        .into_iter()
+        .skip(1)
+        .filter_map(|control| {
+            unsafe { Control::from_ui_control(control as *mut libui_ffi::uiControl) }
+                .downcast::<Button>()
+        })
+        .find(|button| button.text() == text)
+}
+
+/// Tries to close the window, running its `on_closing` callback. Returns `true` if the window
+/// was destroyed.
+pub fn close(window: &Window) -> bool {
+    window.clone().close()
+}
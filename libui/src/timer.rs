@@ -0,0 +1,166 @@
+//! Timers which run callbacks on the GUI thread.
+
+use libui_ffi;
+use panics::catch_panic;
+use std::cell::{Cell, RefCell};
+use std::os::raw::{c_int, c_void};
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use trace;
+use ui::UI;
+
+struct TimerState {
+    callback: RefCell<Option<Box<dyn FnMut() -> bool>>>,
+    cancelled: Cell<bool>,
+}
+
+thread_local! {
+    // Timers which may still be pending, so they can be cancelled when uninitializing.
+    static TIMERS: RefCell<Vec<Weak<TimerState>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A handle to a timer started with [`UI::set_timeout`](struct.UI.html#method.set_timeout) or
+/// [`UI::set_interval`](struct.UI.html#method.set_interval).
+///
+/// Dropping the handle cancels the timer and frees its callback. Use [`detach`](#method.detach)
+/// to keep the timer running without holding on to the handle.
+#[must_use = "the timer is cancelled when its handle is dropped"]
+pub struct Timer {
+    state: Rc<TimerState>,
+    detached: bool,
+}
+
+/// Starts a timer which runs the callback after `delay`, and again every `delay` for as long
+/// as it returns `true`.
+pub fn start(delay: Duration, callback: Box<dyn FnMut() -> bool>) -> Timer {
+    extern "C" fn c_callback(data: *mut c_void) -> c_int {
+        let state = unsafe { &*(data as *const Rc<TimerState>) };
+
+        // The callback is taken out while it runs, so it may cancel its own timer.
+        let callback = state.callback.borrow_mut().take();
+        let repeat = match callback {
+            Some(mut callback) if !state.cancelled.get() => {
+                // A panicking callback is not run again.
+                let repeat = trace::callback("Timer", "tick", || catch_panic(&mut callback))
+                    .unwrap_or(false)
+                    && !state.cancelled.get();
+                if repeat {
+                    *state.callback.borrow_mut() = Some(callback);
+                }
+                repeat
+            }
+            _ => false,
+        };
+
+        if !repeat {
+            unsafe { drop(Box::from_raw(data as *mut Rc<TimerState>)) };
+        }
+        repeat as c_int
+    }
+
+    let state = Rc::new(TimerState {
+        callback: RefCell::new(Some(callback)),
+        cancelled: Cell::new(false),
+    });
+    TIMERS.with(|timers| {
+        let mut timers = timers.borrow_mut();
+        timers.retain(|timer| timer.strong_count() > 0);
+        timers.push(Rc::downgrade(&state));
+    });
+    let millis = delay.as_millis().min(i32::MAX as u128) as c_int;
+    unsafe {
+        let data = Box::into_raw(Box::new(state.clone())) as *mut c_void;
+        libui_ffi::uiTimer(millis, Some(c_callback), data);
+    }
+
+    Timer {
+        state,
+        detached: false,
+    }
+}
+
+/// Cancels all timers, including detached ones, and frees their callbacks. Used when
+/// uninitializing.
+pub fn cancel_all() {
+    let timers = TIMERS.with(|timers| timers.replace(Vec::new()));
+    let callbacks: Vec<_> = timers
+        .iter()
+        .filter_map(Weak::upgrade)
+        .map(|state| {
+            state.cancelled.set(true);
+            let callback = state.callback.borrow_mut().take();
+            callback
+        })
+        .collect();
+    // Dropped after all timers are cancelled, as a callback's destructor might drop a handle.
+    drop(callbacks);
+}
+
+impl Timer {
+    /// Stops the timer. Its callback will not run again and is freed right away.
+    pub fn cancel(self) {}
+
+    /// Returns `true` if the timer will still run its callback.
+    pub fn is_active(&self) -> bool {
+        !self.state.cancelled.get() && self.state.callback.borrow().is_some()
+    }
+
+    /// Lets the timer keep running after the handle is dropped.
+    ///
+    /// A detached interval runs until its callback returns `false`.
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.state.cancelled.set(true);
+            // Free the callback right away, the C side only frees its state on the next tick.
+            let callback = self.state.callback.borrow_mut().take();
+            drop(callback);
+        }
+    }
+}
+
+impl UI {
+    /// Runs the callback once on the GUI thread after `delay` has passed.
+    ///
+    /// The delay has millisecond resolution. The returned [`Timer`](struct.Timer.html)
+    /// cancels the callback when dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libui::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let ui_handle = ui.clone();
+    /// ui.set_timeout(Duration::from_secs(5), move || ui_handle.quit())
+    ///     .detach();
+    /// ui.main();
+    /// ```
+    pub fn set_timeout<F: FnOnce() + 'static>(&self, delay: Duration, callback: F) -> Timer {
+        let mut callback = Some(callback);
+        start(
+            delay,
+            Box::new(move || {
+                if let Some(callback) = callback.take() {
+                    callback();
+                }
+                false
+            }),
+        )
+    }
+
+    /// Runs the callback on the GUI thread every time `interval` has passed, for as long as
+    /// the callback returns `true`.
+    ///
+    /// The interval has millisecond resolution. The returned [`Timer`](struct.Timer.html)
+    /// cancels the callback when dropped.
+    pub fn set_interval<F: FnMut() -> bool + 'static>(&self, interval: Duration, callback: F) -> Timer {
+        start(interval, Box::new(callback))
+    }
+}
@@ -0,0 +1,140 @@
+//! Tracing of callbacks, enabled with the `tracing` feature.
+//!
+//! Every callback runs within a `callback` span at the `DEBUG` level, naming the type of the
+//! control and the event, such as `Button` and `clicked`. Once the callback returns, the span
+//! records how long it took in `duration_us`. Callbacks which block the event loop for longer
+//! than the threshold set with
+//! [`UI::set_slow_callback_threshold`](../struct.UI.html#method.set_slow_callback_threshold)
+//! are logged as warnings.
+//!
+//! Without the feature, callbacks are called directly.
+
+#[cfg(feature = "tracing")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "tracing")]
+use std::time::{Duration, Instant};
+#[cfg(feature = "tracing")]
+use ui::UI;
+
+/// The threshold in microseconds above which callbacks are logged, or `u64::MAX` if disabled.
+#[cfg(feature = "tracing")]
+static SLOW_CALLBACK_MICROS: AtomicU64 = AtomicU64::new(100_000);
+
+/// Runs `f`, the callback handling `event` of a control of type `kind`.
+#[cfg(feature = "tracing")]
+pub fn callback<R, F: FnOnce() -> R>(kind: &'static str, event: &'static str, f: F) -> R {
+    let span = ::tracing::debug_span!(
+        "callback",
+        control = kind,
+        event = event,
+        duration_us = ::tracing::field::Empty
+    );
+    let start = Instant::now();
+    let result = span.in_scope(f);
+    let elapsed = start.elapsed();
+
+    let micros = elapsed.as_micros().min(u64::MAX as u128) as u64;
+    span.record("duration_us", micros);
+    if micros >= SLOW_CALLBACK_MICROS.load(Ordering::Relaxed) {
+        ::tracing::warn!(
+            parent: &span,
+            control = kind,
+            event = event,
+            duration_us = micros,
+            "{}::{} blocked the event loop for {:?}",
+            kind,
+            event,
+            elapsed
+        );
+    }
+    result
+}
+
+/// Runs `f`, the callback handling `event` of a control of type `kind`.
+#[cfg(not(feature = "tracing"))]
+#[inline]
+pub fn callback<R, F: FnOnce() -> R>(_kind: &'static str, _event: &'static str, f: F) -> R {
+    f()
+}
+
+#[cfg(feature = "tracing")]
+impl UI {
+    /// Sets how long a callback may block the event loop before a warning is logged, or
+    /// disables the warning with `None`. The default is 100 milliseconds.
+    ///
+    /// Only available with the `tracing` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libui::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// ui.set_slow_callback_threshold(Some(Duration::from_millis(16)));
+    /// ```
+    pub fn set_slow_callback_threshold(&self, threshold: Option<Duration>) {
+        let micros = threshold.map_or(u64::MAX, |threshold| {
+            threshold.as_micros().min(u64::MAX as u128) as u64
+        });
+        SLOW_CALLBACK_MICROS.store(micros, Ordering::Relaxed);
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Level, Metadata, Subscriber};
+
+    #[derive(Default)]
+    struct Counts {
+        spans: AtomicUsize,
+        warnings: AtomicUsize,
+    }
+
+    /// Counts the warnings and spans it sees.
+    struct Counter(Arc<Counts>);
+
+    impl Subscriber for Counter {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &Attributes) -> Id {
+            Id::from_u64(self.0.spans.fetch_add(1, Ordering::SeqCst) as u64 + 1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event) {
+            if *event.metadata().level() == Level::WARN {
+                self.0.warnings.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn slow_callbacks_are_logged() {
+        let counter = Arc::new(Counts::default());
+        ::tracing::subscriber::with_default(Counter(counter.clone()), || {
+            assert_eq!(callback("Button", "clicked", || 42), 42);
+            assert_eq!(counter.spans.load(Ordering::SeqCst), 1);
+            assert_eq!(counter.warnings.load(Ordering::SeqCst), 0);
+
+            SLOW_CALLBACK_MICROS.store(0, Ordering::Relaxed);
+            callback("Button", "clicked", || ());
+            SLOW_CALLBACK_MICROS.store(100_000, Ordering::Relaxed);
+            assert_eq!(counter.spans.load(Ordering::SeqCst), 2);
+            assert_eq!(counter.warnings.load(Ordering::SeqCst), 1);
+        });
+    }
+}
@@ -1,22 +1,46 @@
-use callback_helpers::{from_void_ptr, to_heap_ptr};
+use callback_helpers::{self, from_void_ptr, invoke, register_callback, to_heap_ptr};
 use error::UIError;
+use executor;
 use ffi_tools;
+use ownership;
+use panics::{self, catch_panic};
+use timer;
+use trace;
 use std::os::raw::{c_int, c_void};
 use libui_ffi;
 
+use std::cell::Cell;
+use std::collections::BTreeMap;
 use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::mem;
+use std::ptr;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
 use std::thread;
 use std::time::{Duration, SystemTime};
 
 use controls::Window;
 
+type QueuedFn = Box<dyn FnOnce() + Send>;
+
+/// Functions queued with `RemoteUI::queue_main` which did not run yet. libui is passed their
+/// key instead of the function, so they can be dropped along with the `UI`, whereas libui
+/// would leak them.
+static QUEUED: Mutex<BTreeMap<usize, QueuedFn>> = Mutex::new(BTreeMap::new());
+static NEXT_QUEUED: AtomicUsize = AtomicUsize::new(0);
+
+fn queued() -> MutexGuard<'static, BTreeMap<usize, QueuedFn>> {
+    QUEUED.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 /// RAII guard for the UI; when dropped, it uninits libUI.
 struct UIToken {
     // This PhantomData prevents UIToken from being Send and Sync
     _pd: PhantomData<*mut ()>,
+    // Identifies this libUI session for `RemoteUI` handles.
+    session: usize,
 }
 
 impl Drop for UIToken {
@@ -25,14 +49,33 @@ impl Drop for UIToken {
             ffi_tools::is_initialized(),
             "Attempted to uninit libUI in UIToken destructor when libUI was not initialized!"
         );
+        // Ends the session first, so no other thread can queue work while uninitializing.
+        ffi_tools::end_session();
+        // Dropped outside of the lock, as a function's destructor might queue another one.
+        let queued = mem::take(&mut *queued());
+        drop(queued);
+        executor::clear_tasks();
+        timer::cancel_all();
         unsafe {
             Window::destroy_all_windows();
+            ownership::destroy_all();
             libui_ffi::uiUninit();
+            callback_helpers::clear_callbacks();
+            panics::clear_handler();
             ffi_tools::unset_initialized();
         }
     }
 }
 
+/// Whether the application should quit, as decided by [`UI::on_should_quit`](struct.UI.html#method.on_should_quit).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuitAction {
+    /// Keep the application running.
+    Keep,
+    /// Quit the application.
+    Quit,
+}
+
 /// A handle to user interface functionality.
 #[derive(Clone)]
 pub struct UI {
@@ -50,6 +93,9 @@ impl UI {
     /// the UI, so do _not_ spin off your UI interactions into an alternative thread. You're likely to
     /// have problems on Mac OS.
     ///
+    /// The calling thread becomes the GUI thread. In debug builds, using controls, menus or
+    /// drawing from any other thread panics, naming the offending function.
+    ///
     /// ```no_run
     /// # use libui::UI;
     /// {
@@ -70,6 +116,7 @@ impl UI {
         if ffi_tools::is_initialized() {
             return Err(UIError::MultipleInitError {});
         };
+        panics::install_hook();
 
         unsafe {
             // Create the magic value needed to init libUI
@@ -83,7 +130,10 @@ impl UI {
                 // Success! We can safely give the user a token allowing them to do UI things.
                 ffi_tools::set_initialized();
                 Ok(UI {
-                    _token: Rc::new(UIToken { _pd: PhantomData }),
+                    _token: Rc::new(UIToken {
+                        _pd: PhantomData,
+                        session: ffi_tools::begin_session(),
+                    }),
                 })
             } else {
                 // Error occurred; copy the string describing it, then free that memory.
@@ -106,11 +156,7 @@ impl UI {
 
     /// Returns an `EventLoop`, a struct that allows you to step over iterations or events in the UI.
     pub fn event_loop(&self) -> EventLoop {
-        unsafe { libui_ffi::uiMainSteps() };
-        return EventLoop {
-            _pd: PhantomData,
-            callback: None,
-        };
+        EventLoop::new()
     }
 
     /// Running this function causes the UI to quit, exiting from [main](struct.UI.html#method.main) and no longer showing any widgets.
@@ -136,9 +182,9 @@ impl UI {
     /// ```
     pub fn queue_main<F: FnMut() + 'static>(&self, callback: F) {
         extern "C" fn c_callback<G: FnMut()>(data: *mut c_void) {
-            unsafe {
-                from_void_ptr::<G>(data)();
-            }
+            // Queued functions run exactly once, so they are freed right after.
+            let mut callback = unsafe { Box::from_raw(data as *mut G) };
+            trace::callback("UI", "queue_main", || catch_panic(callback));
         }
 
         unsafe {
@@ -146,17 +192,100 @@ impl UI {
         }
     }
 
-    /// Set a callback to be run when the application quits.
-    pub fn on_should_quit<F: FnMut() + 'static>(&self, callback: F) {
-        extern "C" fn c_callback<G: FnMut()>(data: *mut c_void) -> i32 {
+    /// Returns a [`RemoteUI`](struct.RemoteUI.html) handle, which can be sent to other threads
+    /// to queue work on the GUI thread.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libui::prelude::*;
+    /// use std::thread;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let remote = ui.remote();
+    ///
+    /// thread::spawn(move || {
+    ///     let result = 6 * 7;
+    ///     remote
+    ///         .queue_main(move || println!("The answer is {}", result))
+    ///         .expect("UI is gone");
+    /// });
+    /// ui.main();
+    /// ```
+    pub fn remote(&self) -> RemoteUI {
+        RemoteUI {
+            session: self._token.session,
+        }
+    }
+
+    /// Set a callback to be run when the application is asked to quit, for example through
+    /// the `Quit` menu item.
+    ///
+    /// The returned [`QuitAction`](enum.QuitAction.html) decides whether the application
+    /// actually quits.
+    pub fn on_should_quit<F: FnMut() -> QuitAction + 'static>(&self, callback: F) {
+        extern "C" fn c_callback<G: FnMut() -> QuitAction>(data: *mut c_void) -> i32 {
             unsafe {
-                from_void_ptr::<G>(data)();
-                0
+                match invoke("UI", "should_quit", || from_void_ptr::<G>(data)()) {
+                    Some(QuitAction::Quit) => 1,
+                    Some(QuitAction::Keep) | None => 0,
+                }
             }
         }
 
         unsafe {
-            libui_ffi::uiOnShouldQuit(Some(c_callback::<F>), to_heap_ptr(callback));
+            libui_ffi::uiOnShouldQuit(
+                Some(c_callback::<F>),
+                register_callback(
+                    ptr::null_mut::<c_void>(),
+                    "should_quit",
+                    c_callback::<F> as *const (),
+                    callback,
+                ),
+            );
+        }
+    }
+}
+
+/// A thread-safe handle to the user interface, obtained from [`UI::remote()`](struct.UI.html#method.remote).
+///
+/// Unlike [`UI`](struct.UI.html), this handle is `Send` and `Sync`, so background threads can use it
+/// to hand their results back to the GUI thread. It does not keep the UI alive; once the `UI`
+/// has been dropped, all operations fail with a
+/// [`NotInitializedError`](enum.UIError.html#variant.NotInitializedError).
+#[derive(Clone, Debug)]
+pub struct RemoteUI {
+    session: usize,
+}
+
+impl RemoteUI {
+    /// Returns `true` if the `UI` this handle was created from is still alive.
+    pub fn is_alive(&self) -> bool {
+        ffi_tools::current_session() == self.session
+    }
+
+    /// Queues a function to be executed on the GUI thread when next possible. Returns
+    /// immediately, not waiting for the function to be executed.
+    ///
+    /// Returns an error if the `UI` has already been dropped. Functions still queued
+    /// when the `UI` is dropped are dropped without being run.
+    pub fn queue_main<F: FnOnce() + Send + 'static>(&self, callback: F) -> Result<(), UIError> {
+        extern "C" fn c_callback(data: *mut c_void) {
+            let callback = queued().remove(&(data as usize));
+            if let Some(callback) = callback {
+                trace::callback("RemoteUI", "queue_main", || catch_panic(callback));
+            }
+        }
+
+        let id = NEXT_QUEUED.fetch_add(1, Ordering::SeqCst);
+        let queued = ffi_tools::with_session(self.session, || {
+            queued().insert(id, Box::new(callback));
+            unsafe { libui_ffi::uiQueueMain(Some(c_callback), id as *mut c_void) };
+        });
+
+        match queued {
+            Some(()) => Ok(()),
+            None => Err(UIError::NotInitializedError()),
         }
     }
 }
@@ -175,6 +304,15 @@ pub struct EventLoop<'s> {
 }
 
 impl<'s> EventLoop<'s> {
+    /// Creates an event loop, for use within the crate where no `UI` is at hand.
+    pub(crate) fn new() -> EventLoop<'s> {
+        unsafe { libui_ffi::uiMainSteps() };
+        EventLoop {
+            _pd: PhantomData,
+            callback: None,
+        }
+    }
+
     /// Set the given callback to run when the event loop is executed.
     /// Note that if integrating other event loops you should consider
     /// the potential benefits and drawbacks of the various run modes.
@@ -190,7 +328,7 @@ impl<'s> EventLoop<'s> {
     pub fn next_tick(&mut self) -> bool {
         let result = unsafe { libui_ffi::uiMainStep(false as c_int) == 1 };
         if let Some(ref mut c) = self.callback {
-            c();
+            trace::callback("EventLoop", "tick", c);
         }
         result
     }
@@ -203,7 +341,7 @@ impl<'s> EventLoop<'s> {
     pub fn next_event_tick(&mut self) -> bool {
         let result = unsafe { libui_ffi::uiMainStep(true as c_int) == 1 };
         if let Some(ref mut c) = self.callback {
-            c();
+            trace::callback("EventLoop", "tick", c);
         }
         result
     }
@@ -218,6 +356,64 @@ impl<'s> EventLoop<'s> {
         }
     }
 
+    /// Hands control to the event loop until the predicate returns `true`, running the callback
+    /// given with `on_tick` after each UI event. The predicate is checked before each event.
+    ///
+    /// Returns `true` once the predicate is satisfied, and `false` if the application should
+    /// quit first.
+    pub fn run_until<P: FnMut() -> bool>(&mut self, mut predicate: P) -> bool {
+        loop {
+            if predicate() {
+                return true;
+            }
+            if !self.next_event_tick() {
+                return false;
+            }
+        }
+    }
+
+    /// Hands control to the event loop for the given duration, running the callback given with
+    /// `on_tick` after each UI event. The event loop is woken up by a timer, so no time is spent
+    /// sleeping.
+    ///
+    /// Returns `true` once the duration has passed, and `false` if the application should
+    /// quit first.
+    pub fn run_for(&mut self, duration: Duration) -> bool {
+        let elapsed = Rc::new(Cell::new(false));
+        let _timer = {
+            let elapsed = elapsed.clone();
+            timer::start(
+                duration,
+                Box::new(move || {
+                    elapsed.set(true);
+                    false
+                }),
+            )
+        };
+        self.run_until(|| elapsed.get())
+    }
+
+    /// Handles all pending UI events and functions queued with
+    /// [`UI::queue_main()`](struct.UI.html#method.queue_main), then returns. Events and
+    /// functions queued while pumping are handled by later calls.
+    ///
+    /// Returns `true` if the application should continue running, and `false`
+    /// if it should quit.
+    pub fn pump_pending(&mut self) -> bool {
+        extern "C" fn c_callback(data: *mut c_void) {
+            let pumped = unsafe { Box::from_raw(data as *mut Rc<Cell<bool>>) };
+            pumped.set(true);
+        }
+
+        // Queued functions run in order, so once this one has run, everything before it has too.
+        let pumped = Rc::new(Cell::new(false));
+        unsafe {
+            let data = Box::into_raw(Box::new(pumped.clone())) as *mut c_void;
+            libui_ffi::uiQueueMain(Some(c_callback), data);
+        }
+        self.run_until(|| pumped.get())
+    }
+
     /// Hands control to the event loop until [`UI::quit()`](struct.UI.html#method.quit) is called,
     /// running the callback given with `on_tick` approximately every
     /// `delay` milliseconds.
@@ -233,7 +429,7 @@ impl<'s> EventLoop<'s> {
                 }
                 if let Ok(duration) = t0.elapsed() {
                     if duration.as_millis() >= delay_ms {
-                        c();
+                        trace::callback("EventLoop", "tick", &mut *c);
                         t0 = SystemTime::now();
                     }
                 } else {
@@ -0,0 +1,195 @@
+#![cfg(feature = "testing")]
+
+extern crate libui;
+extern crate libui_derive;
+
+use libui::controls::*;
+use libui::testing;
+use libui_derive::Choice;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn validated_entry_parses_its_text() {
+    testing::run(|_ui| {
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let mut entry: ValidatedEntry<u8> = ValidatedEntry::new();
+        entry.on_valid_change({
+            let values = values.clone();
+            move |value| values.borrow_mut().push(value)
+        });
+        assert_eq!(entry.error(), None);
+
+        testing::type_text(&mut entry.entry(), "42");
+        assert_eq!(entry.value(), Ok(42));
+        assert_eq!(entry.error(), None);
+
+        testing::type_text(&mut entry.entry(), "256");
+        assert!(entry.value().is_err());
+        assert!(entry.error().is_some());
+
+        entry.set_value(&7);
+        assert_eq!(entry.value(), Ok(7));
+        assert_eq!(entry.error(), None);
+        assert_eq!(*values.borrow(), vec![42]);
+    });
+}
+
+#[test]
+fn releasing_a_slider_runs_the_callback_once() {
+    testing::run(|_ui| {
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let released = Rc::new(RefCell::new(Vec::new()));
+        let mut slider = Slider::new(0, 100);
+        slider.on_changed({
+            let changes = changes.clone();
+            move |value| changes.borrow_mut().push(value)
+        });
+        slider.on_released({
+            let released = released.clone();
+            move |value| released.borrow_mut().push(value)
+        });
+
+        for value in 1..=3 {
+            testing::set_slider_value(&mut slider, value * 10);
+        }
+        assert!(testing::release_slider(&slider));
+        assert_eq!(*changes.borrow(), vec![10, 20, 30]);
+        assert_eq!(*released.borrow(), vec![30]);
+
+        slider.set_range(0, 20);
+        assert_eq!(slider.value(), 20);
+        assert!(slider.has_tooltip());
+        slider.set_has_tooltip(false);
+        assert!(!slider.has_tooltip());
+    });
+}
+
+#[test]
+fn scaled_entries_pass_values_instead_of_steps() {
+    testing::run(|_ui| {
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let mut slider = ScaledSlider::new(0.0, 1.0, 0.05, 2);
+        slider.on_changed({
+            let values = values.clone();
+            move |value| values.borrow_mut().push(value)
+        });
+
+        testing::set_slider_value(&mut slider.entry(), 6);
+        assert_eq!(slider.value(), 0.3);
+        assert_eq!(*values.borrow(), vec![0.3]);
+
+        slider.set_value(2.0);
+        assert_eq!(slider.entry().value(), 20);
+        assert_eq!(slider.value(), 1.0);
+        assert_eq!(values.borrow().len(), 1);
+
+        let mut spinbox = DecimalSpinbox::new(-5.0, 20.0, 2.5, 1);
+        testing::set_spinbox_value(&mut spinbox.entry(), 7);
+        assert_eq!(spinbox.value(), 12.5);
+        spinbox.set_value(0.0);
+        assert_eq!(spinbox.entry().value(), 2);
+    });
+}
+
+#[test]
+fn switching_tabs_runs_the_callback() {
+    testing::run(|_ui| {
+        let selected = Rc::new(RefCell::new(Vec::new()));
+        let mut tabs = TabGroup::new();
+        assert_eq!(tabs.selected(), None);
+        tabs.append("First", Label::new("First"));
+        tabs.append("Third", Label::new("Third"));
+        tabs.insert_at("Second", 1, Label::new("Second"));
+        tabs.on_selected({
+            let selected = selected.clone();
+            move |page| selected.borrow_mut().push(page)
+        });
+
+        assert_eq!(tabs.num_pages(), 3);
+        assert_eq!(tabs.selected(), Some(0));
+        assert!(testing::select_tab(&mut tabs, 2));
+        assert_eq!(tabs.selected(), Some(2));
+        assert!(tabs.set_selected(3).is_err());
+        tabs.set_selected(1).unwrap();
+        assert_eq!(*selected.borrow(), vec![2]);
+
+        assert_eq!(tabs.title(1).as_deref(), Some("Second"));
+        tabs.delete(0).unwrap();
+        assert_eq!(tabs.title(0).as_deref(), Some("Second"));
+        assert_eq!(tabs.title(2), None);
+    });
+}
+
+#[test]
+fn typed_choices_pass_the_selected_item() {
+    testing::run(|_ui| {
+        let selections = Rc::new(RefCell::new(Vec::new()));
+        let mut combobox = TypedCombobox::new(vec!["red", "green", "blue"]);
+        combobox.on_selected({
+            let selections = selections.clone();
+            move |&color| selections.borrow_mut().push(color)
+        });
+        assert_eq!(combobox.selected(), None);
+
+        testing::select_item(&mut combobox.combobox(), 1);
+        assert_eq!(combobox.selected(), Some("green"));
+        assert_eq!(*selections.borrow(), vec!["green"]);
+
+        combobox.set_items(vec!["blue", "green"]);
+        assert_eq!(combobox.combobox().count(), 2);
+        assert_eq!(combobox.selected(), Some("green"));
+        combobox.set_selected(&"red");
+        assert_eq!(combobox.selected(), None);
+
+        let unit = String::from("px");
+        let mut radio_buttons =
+            TypedRadioButtons::with_labels(vec![1, 2, 3], move |size| format!("{} {}", size, unit));
+        radio_buttons.set_selected(&3);
+        radio_buttons.on_selected({
+            let selections = selections.clone();
+            move |&number| selections.borrow_mut().push(["one", "two", "three"][number - 1])
+        });
+        radio_buttons.set_items(vec![3, 4]);
+        assert_eq!(radio_buttons.selected(), Some(3));
+
+        assert!(testing::select_radio_button(&mut radio_buttons.radio_buttons(), 0));
+        assert_eq!(radio_buttons.selected(), Some(3));
+        assert_eq!(*selections.borrow(), vec!["green", "three"]);
+    });
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Choice)]
+enum Quality {
+    Low,
+    #[choice(label = "Very high")]
+    VeryHigh,
+}
+
+#[test]
+fn derived_choices_map_indices_onto_variants() {
+    assert_eq!(Quality::values(), vec![Quality::Low, Quality::VeryHigh]);
+    assert_eq!(Quality::from_index(1), Some(Quality::VeryHigh));
+    assert_eq!(Quality::from_index(2), None);
+    assert_eq!(Quality::VeryHigh.index(), 1);
+    assert_eq!(Quality::VeryHigh.label(), "Very high");
+}
+
+#[test]
+fn derived_choices_offer_their_variants() {
+    testing::run(|_ui| {
+        let selected = Rc::new(RefCell::new(None));
+        let mut combobox = Quality::combobox();
+        combobox.on_selected({
+            let selected = selected.clone();
+            move |&quality| *selected.borrow_mut() = Some(quality)
+        });
+        assert_eq!(combobox.combobox().count(), 2);
+        assert!(testing::select_item(&mut combobox.combobox(), 1));
+        assert_eq!(*selected.borrow(), Some(Quality::VeryHigh));
+
+        let mut radio_buttons = Quality::radio_buttons();
+        radio_buttons.set_selected(&Quality::Low);
+        assert_eq!(radio_buttons.selected(), Some(Quality::Low));
+    });
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "testing")]
+
+extern crate libui;
+#[cfg(feature = "mock")]
+extern crate libui_ffi;
+
+use libui::controls::*;
+use libui::testing;
+
+#[test]
+fn questions_return_the_clicked_button() {
+    testing::run(|ui| {
+        let window = Window::new(ui, "Editor", 200, 100, WindowType::NoMenubar);
+        let buttons = ["Save", "Discard", "Cancel"];
+
+        ui.queue_main({
+            let ui = ui.clone();
+            move || {
+                let question = ui.windows().pop().unwrap();
+                assert_eq!(question.title(), "Unsaved changes");
+                let discard = testing::find_button(&question, "Discard").unwrap();
+                assert!(testing::click(&discard));
+            }
+        });
+        let response = window.ask("Unsaved changes", "Save changes?", &buttons);
+        assert_eq!(response, Response::Button(1));
+        assert!(ui.is_enabled(window.clone()));
+        assert_eq!(ui.windows().pop().unwrap().ptr(), window.ptr());
+
+        let response = window.ask_async("Unsaved changes", "Save changes?", &buttons);
+        assert!(!ui.is_enabled(window.clone()));
+        let question = ui.windows().pop().unwrap();
+        assert!(testing::close(&question));
+        assert!(!question.is_alive());
+        assert_eq!(ui.block_on(response), Some(Response::Closed));
+        assert!(ui.is_enabled(window.clone()));
+        window.destroy();
+    });
+}
+
+#[test]
+fn dialogs_return_the_accepted_value() {
+    testing::run(|ui| {
+        let window = Window::new(ui, "Mail", 200, 100, WindowType::NoMenubar);
+        let mut entry = Entry::new();
+        let mut dialog = Dialog::new(&window, "Log in", 200, 100);
+        dialog.set_content(entry.clone());
+        dialog.add_cancel_button("Cancel");
+        dialog.add_button("Log in", {
+            let entry = entry.clone();
+            move || Some(entry.value()).filter(|user| !user.is_empty())
+        });
+
+        let user = dialog.run_async();
+        assert!(!ui.is_enabled(window.clone()));
+        let log_in = testing::find_button(&dialog.window(), "Log in").unwrap();
+        assert!(testing::click(&log_in));
+        assert!(!dialog.is_finished());
+        testing::type_text(&mut entry, "alice");
+        assert!(testing::click(&log_in));
+        assert!(dialog.is_finished());
+        assert!(!dialog.window().is_alive());
+        assert!(ui.is_enabled(window.clone()));
+        assert_eq!(ui.block_on(user), Some(Some("alice".to_string())));
+
+        let dialog: Dialog<String> = Dialog::new(&window, "Log in", 200, 100);
+        ui.queue_main({
+            let dialog = dialog.clone();
+            move || {
+                testing::close(&dialog.window());
+            }
+        });
+        assert_eq!(dialog.run(), None);
+        assert!(ui.is_enabled(window.clone()));
+        window.destroy();
+    });
+}
+
+// Answering file dialogs needs the mocked libui.
+#[cfg(feature = "mock")]
+#[test]
+fn file_dialogs_apply_their_filters() {
+    use libui_ffi::mock;
+    use std::path::PathBuf;
+
+    testing::run(|ui| {
+        let window = Window::new(ui, "Test", 200, 100, WindowType::NoMenubar);
+        let open = FileDialog::open().filter("Images", &["*.png", "*.jpg"]);
+        mock::respond_to_file_dialog(Some("/photos/holiday.JPG"));
+        assert_eq!(
+            open.show(&window),
+            vec![PathBuf::from("/photos/holiday.JPG")]
+        );
+        assert!(mock::take_message_boxes().is_empty());
+
+        // A refused file is explained, and the dialog shown again.
+        mock::respond_to_file_dialog(Some("/photos/notes.txt"));
+        mock::respond_to_file_dialog(Some("/photos/scan.png"));
+        assert_eq!(open.show(&window), vec![PathBuf::from("/photos/scan.png")]);
+        let refusals = mock::take_message_boxes();
+        assert_eq!(refusals.len(), 1);
+        assert!(refusals[0].error);
+        assert!(refusals[0].description.contains("/photos/notes.txt"));
+        assert!(refusals[0].description.contains("Images (*.png, *.jpg)"));
+
+        mock::respond_to_file_dialog(None);
+        assert!(open.show(&window).is_empty());
+
+        let save = FileDialog::save().filter("Images", &["*.png"]);
+        mock::respond_to_file_dialog(Some("/photos/holiday"));
+        assert_eq!(
+            save.show(&window),
+            vec![PathBuf::from("/photos/holiday.png")]
+        );
+        window.destroy();
+    });
+}
+
+#[test]
+fn dropping_a_dialog_destroys_its_window() {
+    testing::run(|ui| {
+        let window = Window::new(ui, "Mail", 200, 100, WindowType::NoMenubar);
+        let mut dialog: Dialog<()> = Dialog::new(&window, "Unused", 200, 100);
+        dialog.add_button("OK", || Some(()));
+        dialog.add_cancel_button("Cancel");
+        let dialog_window = dialog.window();
+        assert!(dialog_window.is_alive());
+        drop(dialog);
+        assert!(!dialog_window.is_alive());
+        assert!(!ui.windows().iter().any(|window| window.title() == "Unused"));
+
+        // A running dialog finishes even if its handles are dropped.
+        let dialog: Dialog<()> = Dialog::new(&window, "Running", 200, 100);
+        let dialog_window = dialog.window();
+        let result = dialog.run_async();
+        drop(dialog);
+        assert!(dialog_window.is_alive());
+        assert!(testing::close(&dialog_window));
+        assert_eq!(ui.block_on(result), Some(None));
+        assert!(ui.is_enabled(window.clone()));
+        window.destroy();
+    });
+}
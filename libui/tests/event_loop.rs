@@ -0,0 +1,187 @@
+#![cfg(feature = "testing")]
+
+extern crate libui;
+
+use libui::testing;
+use std::cell::{Cell, RefCell};
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::task::Poll;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn remote_handles_queue_functions_from_other_threads() {
+    testing::run(|ui| {
+        let remote = ui.remote();
+        assert!(remote.is_alive());
+
+        let gui_thread = thread::current().id();
+        let (sender, received) = mpsc::channel();
+        let worker = thread::spawn(move || {
+            let worker = thread::current().id();
+            remote
+                .queue_main(move || sender.send((worker, thread::current().id())).unwrap())
+                .unwrap();
+            worker
+        });
+        let worker = worker.join().unwrap();
+
+        let mut ran_on = None;
+        assert!(ui.event_loop().run_until(|| {
+            ran_on = received.try_recv().ok();
+            ran_on.is_some()
+        }));
+        assert_eq!(ran_on, Some((worker, gui_thread)));
+    });
+}
+
+#[test]
+fn local_tasks_await_background_work() {
+    testing::run(|ui| {
+        // Not `Send`, which local tasks need not be.
+        let answer = Rc::new(Cell::new(0));
+        let mut worker = ui.spawn_blocking(|| 6 * 7);
+        let task = ui.spawn_local({
+            let answer = answer.clone();
+            future::poll_fn(move |context| {
+                Pin::new(&mut worker).poll(context).map(|result| {
+                    answer.set(result.unwrap());
+                    "done"
+                })
+            })
+        });
+        assert_eq!(ui.block_on(task), Some(Some("done")));
+        assert_eq!(answer.get(), 42);
+
+        let failed = ui.spawn_blocking(|| panic!("failed"));
+        assert!(ui.block_on(failed).unwrap().is_err());
+    });
+}
+
+#[test]
+fn panicking_tasks_resolve_to_none() {
+    testing::run(|ui| {
+        let panics = Rc::new(RefCell::new(Vec::new()));
+        // Unlike the default handler, this one does not quit.
+        ui.set_panic_handler({
+            let panics = panics.clone();
+            move |panic| panics.borrow_mut().push(panic.message().to_string())
+        });
+
+        let task = ui.spawn_local(future::poll_fn(|_| -> Poll<()> { panic!("failed") }));
+        assert_eq!(ui.block_on(task), Some(None));
+        assert_eq!(ui.block_on(future::poll_fn(|_| -> Poll<()> { panic!("failed") })), None);
+        assert_eq!(*panics.borrow(), vec!["failed", "failed"]);
+    });
+}
+
+#[test]
+fn detached_tasks_keep_running() {
+    testing::run(|ui| {
+        let polled = Rc::new(Cell::new(false));
+        drop(ui.spawn_local({
+            let polled = polled.clone();
+            future::poll_fn(move |_| {
+                polled.set(true);
+                Poll::Ready(())
+            })
+        }));
+        assert!(!polled.get());
+        ui.event_loop().pump_pending();
+        assert!(polled.get());
+    });
+}
+
+#[test]
+fn timeouts_run_once_unless_cancelled() {
+    testing::run(|ui| {
+        let runs = Rc::new(Cell::new(0));
+        let timeout = ui.set_timeout(Duration::from_millis(10), {
+            let runs = runs.clone();
+            move || runs.set(runs.get() + 1)
+        });
+        assert!(timeout.is_active());
+        let cancelled = ui.set_timeout(Duration::from_millis(10), || panic!("cancelled"));
+        cancelled.cancel();
+
+        ui.event_loop().run_for(Duration::from_millis(50));
+        assert_eq!(runs.get(), 1);
+        assert!(!timeout.is_active());
+        ui.event_loop().run_for(Duration::from_millis(30));
+        assert_eq!(runs.get(), 1);
+    });
+}
+
+#[test]
+fn intervals_run_until_their_callback_returns_false() {
+    testing::run(|ui| {
+        let runs = Rc::new(Cell::new(0));
+        let interval = ui.set_interval(Duration::from_millis(5), {
+            let runs = runs.clone();
+            move || {
+                runs.set(runs.get() + 1);
+                runs.get() < 3
+            }
+        });
+        assert!(ui.event_loop().run_until(|| runs.get() == 3));
+        ui.event_loop().run_for(Duration::from_millis(30));
+        assert_eq!(runs.get(), 3);
+        assert!(!interval.is_active());
+
+        // An interval may also cancel itself by dropping its handle.
+        let handle = Rc::new(RefCell::new(None));
+        let runs = Rc::new(Cell::new(0));
+        *handle.borrow_mut() = Some(ui.set_interval(Duration::from_millis(5), {
+            let handle = handle.clone();
+            let runs = runs.clone();
+            move || {
+                runs.set(runs.get() + 1);
+                drop(handle.borrow_mut().take());
+                true
+            }
+        }));
+        ui.event_loop().run_for(Duration::from_millis(50));
+        assert_eq!(runs.get(), 1);
+        assert!(handle.borrow().is_none());
+    });
+}
+
+#[test]
+fn the_event_loop_runs_until_told_to_stop() {
+    testing::run(|ui| {
+        let ran = Rc::new(Cell::new(false));
+        ui.queue_main({
+            let ran = ran.clone();
+            move || ran.set(true)
+        });
+        assert!(ui.event_loop().run_until(|| ran.get()));
+
+        let started = Instant::now();
+        assert!(ui.event_loop().run_for(Duration::from_millis(20)));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    });
+}
+
+#[test]
+fn pumping_runs_only_what_was_pending() {
+    testing::run(|ui| {
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        ui.queue_main({
+            let ui = ui.clone();
+            let runs = runs.clone();
+            move || {
+                runs.borrow_mut().push("queued");
+                let runs = runs.clone();
+                ui.queue_main(move || runs.borrow_mut().push("queued while pumping"));
+            }
+        });
+
+        assert!(ui.event_loop().pump_pending());
+        assert_eq!(*runs.borrow(), vec!["queued"]);
+        assert!(ui.event_loop().pump_pending());
+        assert_eq!(*runs.borrow(), vec!["queued", "queued while pumping"]);
+    });
+}
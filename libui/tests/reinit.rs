@@ -0,0 +1,130 @@
+// Runs on the real backend as well, which only the `testing` feature provides a display for.
+#![cfg(any(feature = "mock", feature = "testing"))]
+
+extern crate libui;
+#[cfg(feature = "mock")]
+extern crate libui_ffi;
+
+use libui::controls::*;
+use libui::prelude::*;
+#[cfg(feature = "mock")]
+use libui_ffi::mock;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct Handler {
+    _alive: Rc<()>,
+}
+
+impl AreaHandler for Handler {}
+
+struct DataSource {
+    _alive: Rc<()>,
+}
+
+impl TableDataSource for DataSource {
+    fn num_columns(&mut self) -> i32 {
+        1
+    }
+
+    fn num_rows(&mut self) -> i32 {
+        1
+    }
+
+    fn column_type(&mut self, _column: i32) -> TableValueType {
+        TableValueType::String
+    }
+
+    fn cell(&mut self, _column: i32, _row: i32) -> TableValue {
+        TableValue::String(String::from("cell"))
+    }
+
+    fn set_cell(&mut self, _column: i32, _row: i32, _value: TableValue) {}
+}
+
+/// Builds a window using callbacks, a timer, an area and a table, all of which hold on to
+/// `alive`.
+fn build_ui(ui: &UI, alive: &Rc<()>) -> Window {
+    let mut window = Window::new(ui, "Test", 200, 100, WindowType::HasMenubar);
+    let mut vbox = VerticalBox::new();
+
+    let mut button = Button::new("Click me");
+    button.on_clicked({
+        let alive = alive.clone();
+        move |_| drop(alive.clone())
+    });
+    vbox.append(button, LayoutStrategy::Compact);
+
+    vbox.append(
+        Area::new(Box::new(Handler {
+            _alive: alive.clone(),
+        })),
+        LayoutStrategy::Stretchy,
+    );
+
+    let model = Rc::new(RefCell::new(TableModel::new(Rc::new(RefCell::new(
+        DataSource {
+            _alive: alive.clone(),
+        },
+    )))));
+    let mut table = Table::new(TableParameters::new(model));
+    table.append_text_column("Column", 0, Table::COLUMN_READONLY);
+    vbox.append(table, LayoutStrategy::Stretchy);
+
+    window.set_child(vbox);
+    window.show();
+
+    ui.set_interval(Duration::from_secs(60), {
+        let alive = alive.clone();
+        move || {
+            drop(alive.clone());
+            true
+        }
+    })
+    .detach();
+    window
+}
+
+#[test]
+fn reinitializing_starts_from_a_clean_state() {
+    #[cfg(feature = "testing")]
+    libui::testing::ensure_display();
+
+    let alive = Rc::new(());
+    let mut previous: Option<Window> = None;
+    for _ in 0..20 {
+        let ui = UI::init().expect("failed to initialize libui");
+        let window = build_ui(&ui, &alive);
+        assert!(Rc::strong_count(&alive) > 1);
+        assert_eq!(ui.windows().len(), 1);
+        #[cfg(feature = "mock")]
+        assert_eq!(mock::windows(), vec![window.ptr()]);
+        // The last window is not alive, even if the new one took its place.
+        assert!(!previous.take().is_some_and(|previous| previous.is_alive()));
+
+        // Handles dropped after the UI must not touch the next session.
+        let remote = ui.remote();
+        let queued = Arc::new(());
+        remote
+            .queue_main({
+                let queued = queued.clone();
+                move || drop(queued)
+            })
+            .unwrap();
+        drop(ui);
+        // Functions which did not run are dropped along with the UI.
+        assert_eq!(Arc::strong_count(&queued), 1);
+        assert!(!remote.is_alive());
+        assert!(remote.queue_main(|| unreachable!()).is_err());
+        assert!(!window.is_alive());
+        #[cfg(feature = "mock")]
+        {
+            assert!(!mock::is_initialized());
+            assert!(mock::windows().is_empty());
+        }
+        assert_eq!(Rc::strong_count(&alive), 1);
+        previous = Some(window);
+    }
+}
@@ -0,0 +1,194 @@
+#![cfg(feature = "testing")]
+
+extern crate libui;
+
+use libui::controls::*;
+use libui::testing;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+
+#[test]
+fn clicking_a_button_runs_its_callback() {
+    testing::run(|_ui| {
+        let label = Label::new("Not clicked");
+        let mut button = Button::new("Click me");
+        button.on_clicked({
+            let mut label = label.clone();
+            move |_| label.set_text("Clicked")
+        });
+
+        assert!(testing::click(&button));
+        assert_eq!(label.text(), "Clicked");
+    });
+}
+
+#[test]
+fn typing_into_an_entry_passes_the_text() {
+    testing::run(|_ui| {
+        let typed = Rc::new(RefCell::new(String::new()));
+        let mut entry = Entry::new();
+        entry.on_changed({
+            let typed = typed.clone();
+            move |text| *typed.borrow_mut() = text
+        });
+
+        assert!(testing::type_text(&mut entry, "hello"));
+        assert_eq!(entry.value(), "hello");
+        assert_eq!(*typed.borrow(), "hello");
+    });
+}
+
+#[test]
+fn toggling_a_checkbox_updates_its_state() {
+    testing::run(|ui| {
+        let mut checkbox = Checkbox::new("Check me");
+        assert!(!testing::toggle(&mut checkbox));
+        assert!(checkbox.checked());
+
+        let toggled = Rc::new(RefCell::new(None));
+        checkbox.on_toggled(ui, {
+            let toggled = toggled.clone();
+            move |checked| *toggled.borrow_mut() = Some(checked)
+        });
+        assert!(testing::toggle(&mut checkbox));
+        assert_eq!(*toggled.borrow(), Some(false));
+    });
+}
+
+#[test]
+fn picking_a_color_keeps_the_button_alive() {
+    testing::run(|ui| {
+        let picked = Rc::new(RefCell::new(Vec::new()));
+        let mut button = ColorButton::new();
+        button.on_changed(ui, {
+            let picked = picked.clone();
+            move |button| picked.borrow_mut().push(button.color())
+        });
+
+        assert!(testing::pick_color(&mut button, 1.0, 0.0, 0.0, 1.0));
+        assert!(testing::pick_color(&mut button, 0.0, 1.0, 0.0, 1.0));
+        assert_eq!(
+            *picked.borrow(),
+            vec![(1.0, 0.0, 0.0, 1.0), (0.0, 1.0, 0.0, 1.0)]
+        );
+
+        button.set_color(0.0, 0.0, 1.0, 1.0);
+        assert_eq!(button.color(), (0.0, 0.0, 1.0, 1.0));
+        let mut layout = VerticalBox::new();
+        layout.append(button, LayoutStrategy::Compact);
+    });
+}
+
+#[test]
+fn selecting_an_item_runs_the_callback() {
+    testing::run(|ui| {
+        let selected = Rc::new(RefCell::new(-1));
+        let mut combobox = Combobox::new();
+        combobox.append("First");
+        combobox.append("Second");
+        combobox.on_selected(ui, {
+            let selected = selected.clone();
+            move |index| *selected.borrow_mut() = index
+        });
+
+        assert!(testing::select_item(&mut combobox, 1));
+        assert_eq!(combobox.selected(), 1);
+        assert_eq!(*selected.borrow(), 1);
+    });
+}
+
+#[test]
+fn closing_a_window_respects_the_close_action() {
+    testing::run(|ui| {
+        let mut window = Window::new(ui, "Test", 200, 100, WindowType::NoMenubar);
+        window.on_closing(ui, |_| CloseAction::Keep);
+        assert!(!testing::close(&window));
+
+        window.on_closing(ui, |_| CloseAction::Hide);
+        assert!(!testing::close(&window));
+
+        let button = Button::new("Child");
+        window.set_child(button.clone());
+        window.on_closing(ui, |_| CloseAction::Destroy);
+        assert!(testing::close(&window));
+        assert!(!window.is_alive());
+        assert!(!testing::close(&window));
+        // The window is freed along with the button once the last handle to it is dropped.
+        assert_eq!(button.text(), "Child");
+        drop(window);
+
+        // New controls may be created where the button was, which its stale handle must not
+        // release when dropped.
+        let buttons: Vec<Button> = (0..16).map(|_| Button::new("New")).collect();
+        drop(button);
+        for button in &buttons {
+            assert_eq!(button.text(), "New");
+        }
+    });
+}
+
+struct Rows;
+
+impl TableDataSource for Rows {
+    fn num_columns(&mut self) -> i32 {
+        1
+    }
+
+    fn num_rows(&mut self) -> i32 {
+        2
+    }
+
+    fn column_type(&mut self, _column: i32) -> TableValueType {
+        TableValueType::String
+    }
+
+    fn cell(&mut self, _column: i32, row: i32) -> TableValue {
+        TableValue::String(format!("Row {}", row))
+    }
+
+    fn set_cell(&mut self, _column: i32, _row: i32, _value: TableValue) {}
+}
+
+#[test]
+fn closing_a_window_frees_table_models_after_the_tables() {
+    testing::run(|ui| {
+        let model = Rc::new(RefCell::new(TableModel::new(Rc::new(RefCell::new(Rows)))));
+        let mut window = Window::new(ui, "Table", 200, 100, WindowType::NoMenubar);
+        let mut layout = VerticalBox::new();
+        layout.append(
+            Table::new(TableParameters::new(model)),
+            LayoutStrategy::Stretchy,
+        );
+        window.set_child(layout);
+
+        window.on_closing(ui, |_| CloseAction::Destroy);
+        assert!(testing::close(&window));
+        drop(window);
+    });
+}
+
+#[test]
+fn panics_in_tests_are_passed_on() {
+    let result = std::panic::catch_unwind(|| testing::run(|_ui| panic!("failed")));
+    assert!(result.is_err());
+
+    // The GUI thread is still usable afterwards.
+    assert_eq!(testing::run(|_ui| 42), 42);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn using_a_control_on_another_thread_panics() {
+    testing::run(|_ui| {
+        let button = Button::new("Click me");
+        let ptr = button.ptr() as usize;
+
+        let result = thread::spawn(move || {
+            let _button = unsafe { Button::from_raw(ptr as *mut _) };
+        })
+        .join();
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("`Button::from_raw`"), "{}", message);
+    });
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "testing")]
+
+extern crate libui;
+
+use libui::controls::*;
+use libui::testing;
+use libui::UI;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn resizing_a_window_runs_the_callback() {
+    testing::run(|ui| {
+        let sizes = Rc::new(RefCell::new(Vec::new()));
+        let mut window = Window::new(ui, "Test", 200, 100, WindowType::NoMenubar);
+        window.on_content_size_changed({
+            let sizes = sizes.clone();
+            move |window| sizes.borrow_mut().push(window.content_size())
+        });
+
+        assert!(testing::resize_window(&mut window, 640, 480));
+        assert_eq!(window.content_size(), (640, 480));
+        assert_eq!(*sizes.borrow(), vec![(640, 480)]);
+
+        window.set_borderless(true);
+        assert!(window.borderless());
+        window.set_fullscreen(true);
+        assert!(window.fullscreen());
+        window.set_fullscreen(false);
+        assert!(!window.fullscreen());
+        window.destroy();
+    });
+}
+
+// Which window has the focus is up to the window manager, unless libui is mocked.
+#[cfg(feature = "mock")]
+#[test]
+fn showing_a_window_moves_the_focus() {
+    testing::run(|ui| {
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let mut windows = Vec::new();
+        for title in &["First", "Second"] {
+            let mut window = Window::new(ui, title, 200, 100, WindowType::NoMenubar);
+            window.on_focus_changed({
+                let changes = changes.clone();
+                move |window| changes.borrow_mut().push((window.title(), window.focused()))
+            });
+            windows.push(window);
+        }
+
+        windows[0].show();
+        assert!(windows[0].focused());
+        windows[1].show();
+        assert!(!windows[0].focused());
+        assert!(windows[1].focused());
+        assert_eq!(
+            *changes.borrow(),
+            vec![
+                ("First".to_string(), true),
+                ("First".to_string(), false),
+                ("Second".to_string(), true),
+            ]
+        );
+        for window in windows {
+            window.destroy();
+        }
+    });
+}
+
+#[test]
+fn windows_are_listed_until_destroyed() {
+    testing::run(|ui| {
+        let destroyed = Rc::new(RefCell::new(Vec::new()));
+        let mut windows = Vec::new();
+        for (title, action) in &[("Kept", CloseAction::Keep), ("Closed", CloseAction::Destroy)] {
+            let action = *action;
+            let mut window = Window::new(ui, title, 200, 100, WindowType::NoMenubar);
+            window.on_closing(ui, move |_| action);
+            window.on_destroyed({
+                let destroyed = destroyed.clone();
+                move |window| destroyed.borrow_mut().push(window.title())
+            });
+            windows.push(window);
+        }
+        let titles = |ui: &UI| ui.windows().iter().map(Window::title).collect::<Vec<_>>();
+        assert_eq!(titles(ui), vec!["Kept", "Closed"]);
+
+        assert!(!windows[0].close());
+        assert!(windows[0].is_alive());
+        assert!(windows[1].close());
+        assert!(!windows[1].is_alive());
+        assert_eq!(titles(ui), vec!["Kept"]);
+        assert_eq!(*destroyed.borrow(), vec!["Closed"]);
+
+        let kept = windows.remove(0);
+        kept.destroy();
+        assert!(titles(ui).is_empty());
+        assert_eq!(*destroyed.borrow(), vec!["Closed", "Kept"]);
+    });
+}
+
+#[test]
+fn windows_left_open_are_destroyed_after_the_test() {
+    testing::run(|ui| {
+        Window::new(ui, "Left open", 200, 100, WindowType::NoMenubar).show();
+    });
+    testing::run(|ui| assert!(ui.windows().is_empty()));
+}